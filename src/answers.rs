@@ -0,0 +1,123 @@
+//! Expected answers, to check that the solutions still solve our inputs
+//!
+//! The answers file has one answer per line, as `<day> <part> <answer>`.
+//! Empty lines and lines starting with `#` are ignored.
+
+use std::collections::HashMap;
+use std::fmt::{self, Display};
+
+use crate::error::{parse_token, ParseError};
+
+/// Where the answers are read from by default
+pub const DEFAULT_PATH: &str = "inputs/answers";
+
+/// Expected answer of each day and part
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Answers {
+    answers: HashMap<(u8, u8), String>,
+}
+
+/// Outcome of comparing an answer to the expected one
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Verdict {
+    Pass,
+    Fail {
+        expected: String,
+    },
+    /// There is no expected answer
+    Missing,
+}
+
+impl Answers {
+    pub fn parse(input: &str) -> Result<Answers, ParseError> {
+        let mut answers = HashMap::new();
+
+        for line in input.lines() {
+            if line.trim().is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            let mut tokens = line.splitn(3, ' ');
+            let day = parse_token(input, tokens.next().unwrap(), "a day")?;
+            let part = match tokens.next() {
+                Some(p @ ("1" | "2")) => parse_token(input, p, "a part")?,
+                Some(p) => return Err(ParseError::invalid(input, p, "part 1 or 2")),
+                None => return Err(ParseError::missing(input, line, "a part")),
+            };
+            let answer = match tokens.next().map(str::trim) {
+                Some(a) if !a.is_empty() => a,
+                _ => return Err(ParseError::missing(input, line, "an answer")),
+            };
+
+            answers.insert((day, part), answer.to_string());
+        }
+
+        Ok(Answers { answers })
+    }
+
+    pub fn get(&self, day: u8, part: u8) -> Option<&str> {
+        self.answers.get(&(day, part)).map(String::as_str)
+    }
+
+    /// Whether there is any expected answer for `day`
+    pub fn has_day(&self, day: u8) -> bool {
+        self.get(day, 1).is_some() || self.get(day, 2).is_some()
+    }
+
+    /// Compares `answer` to the expected answer of `day` and `part`
+    pub fn check(&self, day: u8, part: u8, answer: &str) -> Verdict {
+        match self.get(day, part) {
+            Some(expected) if expected == answer => Verdict::Pass,
+            Some(expected) => Verdict::Fail {
+                expected: expected.to_string(),
+            },
+            None => Verdict::Missing,
+        }
+    }
+}
+
+impl Display for Verdict {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Verdict::Pass => write!(f, "pass"),
+            Verdict::Fail { expected } => write!(f, "FAIL (expected {})", expected),
+            Verdict::Missing => write!(f, "missing"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse() {
+        let answers = Answers::parse("# day part answer\n1 1 1624\n\n13 2 LKREBPRK\n").unwrap();
+        assert_eq!(answers.get(1, 1), Some("1624"));
+        assert_eq!(answers.get(13, 2), Some("LKREBPRK"));
+        assert_eq!(answers.get(1, 2), None);
+        assert!(answers.has_day(13));
+        assert!(!answers.has_day(2));
+
+        let err = Answers::parse("1 1 5\n1 3 5\n").unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "<input>:2:3: expected part 1 or 2, found '3'"
+        );
+        assert!(Answers::parse("1 1\n").is_err());
+        assert!(Answers::parse("x 1 5\n").is_err());
+    }
+
+    #[test]
+    fn test_check() {
+        let answers = Answers::parse("1 1 1624\n").unwrap();
+        assert_eq!(answers.check(1, 1, "1624"), Verdict::Pass);
+        assert_eq!(
+            answers.check(1, 1, "1625"),
+            Verdict::Fail {
+                expected: String::from("1624")
+            }
+        );
+        assert_eq!(answers.check(1, 2, "1653"), Verdict::Missing);
+    }
+}
@@ -0,0 +1,127 @@
+//! Timing of the parse and solve steps of a day
+
+use std::fmt::{self, Display};
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+
+use crate::error::ParseError;
+use crate::Solution;
+
+/// A step of solving a day that is timed on its own
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Stage {
+    Parse,
+    PartOne,
+    PartTwo,
+}
+
+impl Display for Stage {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Stage::Parse => "parse",
+            Stage::PartOne => "part1",
+            Stage::PartTwo => "part2",
+        };
+        f.pad(name)
+    }
+}
+
+/// All measured durations of one stage of a day
+#[derive(Debug, Clone)]
+pub struct Timing {
+    pub day: u8,
+    pub stage: Stage,
+    /// Sorted, shortest first
+    pub samples: Vec<Duration>,
+}
+
+impl Timing {
+    fn new(day: u8, stage: Stage, mut samples: Vec<Duration>) -> Timing {
+        samples.sort_unstable();
+        Timing {
+            day,
+            stage,
+            samples,
+        }
+    }
+
+    pub fn min(&self) -> Duration {
+        self.samples[0]
+    }
+
+    pub fn median(&self) -> Duration {
+        self.samples[self.samples.len() / 2]
+    }
+
+    pub fn max(&self) -> Duration {
+        self.samples[self.samples.len() - 1]
+    }
+}
+
+/// Runs `f` `reps` times and measures every run
+fn sample<T>(reps: usize, mut f: impl FnMut() -> T) -> Vec<Duration> {
+    (0..reps)
+        .map(|_| {
+            let start = Instant::now();
+            black_box(f());
+            start.elapsed()
+        })
+        .collect()
+}
+
+/// Times parsing `input` and solving both parts, each `reps` (at least 1) times
+pub fn bench_solution<S: Solution>(input: &str, reps: usize) -> Result<Vec<Timing>, ParseError> {
+    assert!(reps > 0, "need at least one repetition");
+
+    let parsed = S::parse(input)?;
+    let parse = sample(reps, || S::parse(black_box(input)));
+    let part_one = sample(reps, || S::part_one(black_box(&parsed)));
+    let part_two = sample(reps, || S::part_two(black_box(&parsed)));
+
+    Ok(vec![
+        Timing::new(S::DAY, Stage::Parse, parse),
+        Timing::new(S::DAY, Stage::PartOne, part_one),
+        Timing::new(S::DAY, Stage::PartTwo, part_two),
+    ])
+}
+
+/// Summary of `timings` as CSV, in nanoseconds
+pub fn to_csv(timings: &[Timing]) -> String {
+    let mut csv = String::from("day,stage,reps,min_ns,median_ns,max_ns\n");
+    for t in timings {
+        csv.push_str(&format!(
+            "{},{},{},{},{},{}\n",
+            t.day,
+            t.stage,
+            t.samples.len(),
+            t.min().as_nanos(),
+            t.median().as_nanos(),
+            t.max().as_nanos()
+        ));
+    }
+    csv
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::day1::Day1;
+
+    #[test]
+    fn test_bench_solution() {
+        let timings = bench_solution::<Day1>("199\n200\n208\n210\n", 5).unwrap();
+
+        assert_eq!(timings.len(), 3);
+        for t in &timings {
+            assert_eq!(t.day, 1);
+            assert_eq!(t.samples.len(), 5);
+            assert!(t.min() <= t.median() && t.median() <= t.max());
+        }
+
+        let csv = to_csv(&timings);
+        assert_eq!(csv.lines().count(), 4);
+        assert!(csv.lines().nth(2).unwrap().starts_with("1,part1,5,"));
+
+        assert!(bench_solution::<Day1>("1\nx\n", 1).is_err());
+    }
+}
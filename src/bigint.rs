@@ -0,0 +1,242 @@
+//! A small arbitrary-precision unsigned integer, for counts that grow exponentially
+
+use std::cmp::Ordering;
+use std::fmt::{self, Display};
+use std::iter::Sum;
+use std::ops::{Add, AddAssign, Mul, Sub, SubAssign};
+
+/// Every limb holds 9 decimal digits, which keeps printing simple
+const BASE: u64 = 1_000_000_000;
+
+/// An unsigned integer of any size
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct BigUint {
+    /// Digits in base [`BASE`], least significant first, without leading zeros
+    limbs: Vec<u32>,
+}
+
+impl BigUint {
+    pub fn zero() -> BigUint {
+        BigUint { limbs: Vec::new() }
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.limbs.is_empty()
+    }
+
+    /// Drops leading zero limbs
+    fn normalize(&mut self) {
+        while self.limbs.last() == Some(&0) {
+            self.limbs.pop();
+        }
+    }
+}
+
+impl From<u64> for BigUint {
+    fn from(mut n: u64) -> BigUint {
+        let mut limbs = Vec::new();
+        while n > 0 {
+            limbs.push((n % BASE) as u32);
+            n /= BASE;
+        }
+        BigUint { limbs }
+    }
+}
+
+impl From<usize> for BigUint {
+    fn from(n: usize) -> BigUint {
+        BigUint::from(n as u64)
+    }
+}
+
+impl AddAssign<&BigUint> for BigUint {
+    fn add_assign(&mut self, other: &BigUint) {
+        if self.limbs.len() < other.limbs.len() {
+            self.limbs.resize(other.limbs.len(), 0);
+        }
+
+        let mut carry = 0;
+        for (i, limb) in self.limbs.iter_mut().enumerate() {
+            let sum = *limb as u64 + other.limbs.get(i).copied().unwrap_or(0) as u64 + carry;
+            *limb = (sum % BASE) as u32;
+            carry = sum / BASE;
+            if carry == 0 && i >= other.limbs.len() {
+                break;
+            }
+        }
+        if carry > 0 {
+            self.limbs.push(carry as u32);
+        }
+    }
+}
+
+impl Add<&BigUint> for BigUint {
+    type Output = BigUint;
+
+    fn add(mut self, other: &BigUint) -> BigUint {
+        self += other;
+        self
+    }
+}
+
+impl Add for BigUint {
+    type Output = BigUint;
+
+    fn add(self, other: BigUint) -> BigUint {
+        self + &other
+    }
+}
+
+impl SubAssign<&BigUint> for BigUint {
+    /// Panics if `other` is larger
+    fn sub_assign(&mut self, other: &BigUint) {
+        assert!(*self >= *other, "attempt to subtract with overflow");
+
+        let mut borrow = 0;
+        for (i, limb) in self.limbs.iter_mut().enumerate() {
+            let subtrahend = other.limbs.get(i).copied().unwrap_or(0) as u64 + borrow;
+            if subtrahend == 0 && i >= other.limbs.len() {
+                break;
+            }
+            let (value, next_borrow) = match (*limb as u64) < subtrahend {
+                true => (*limb as u64 + BASE - subtrahend, 1),
+                false => (*limb as u64 - subtrahend, 0),
+            };
+            *limb = value as u32;
+            borrow = next_borrow;
+        }
+        self.normalize();
+    }
+}
+
+impl Sub<&BigUint> for BigUint {
+    type Output = BigUint;
+
+    fn sub(mut self, other: &BigUint) -> BigUint {
+        self -= other;
+        self
+    }
+}
+
+impl Sub for BigUint {
+    type Output = BigUint;
+
+    fn sub(self, other: BigUint) -> BigUint {
+        self - &other
+    }
+}
+
+impl Mul for &BigUint {
+    type Output = BigUint;
+
+    fn mul(self, other: &BigUint) -> BigUint {
+        if self.is_zero() || other.is_zero() {
+            return BigUint::zero();
+        }
+
+        let mut limbs = vec![0u64; self.limbs.len() + other.limbs.len()];
+        for (i, &a) in self.limbs.iter().enumerate() {
+            let mut carry = 0;
+            for (j, &b) in other.limbs.iter().enumerate() {
+                // at most (BASE - 1)^2 + 2 * (BASE - 1), which fits into u64
+                let product = limbs[i + j] + a as u64 * b as u64 + carry;
+                limbs[i + j] = product % BASE;
+                carry = product / BASE;
+            }
+            limbs[i + other.limbs.len()] += carry;
+        }
+
+        let mut product = BigUint {
+            limbs: limbs.into_iter().map(|l| l as u32).collect(),
+        };
+        product.normalize();
+        product
+    }
+}
+
+impl Mul for BigUint {
+    type Output = BigUint;
+
+    fn mul(self, other: BigUint) -> BigUint {
+        &self * &other
+    }
+}
+
+impl Ord for BigUint {
+    fn cmp(&self, other: &BigUint) -> Ordering {
+        self.limbs
+            .len()
+            .cmp(&other.limbs.len())
+            .then_with(|| self.limbs.iter().rev().cmp(other.limbs.iter().rev()))
+    }
+}
+
+impl PartialOrd for BigUint {
+    fn partial_cmp(&self, other: &BigUint) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<'a> Sum<&'a BigUint> for BigUint {
+    fn sum<I: Iterator<Item = &'a BigUint>>(iter: I) -> BigUint {
+        iter.fold(BigUint::zero(), |sum, n| sum + n)
+    }
+}
+
+impl Sum for BigUint {
+    fn sum<I: Iterator<Item = BigUint>>(iter: I) -> BigUint {
+        iter.fold(BigUint::zero(), |sum, n| sum + n)
+    }
+}
+
+impl Display for BigUint {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut digits = match self.limbs.last() {
+            Some(top) => top.to_string(),
+            None => String::from("0"),
+        };
+        for limb in self.limbs.iter().rev().skip(1) {
+            digits.push_str(&format!("{:09}", limb));
+        }
+        f.pad_integral(true, "", &digits)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_arithmetic() {
+        let a = BigUint::from(u64::MAX);
+        let b = BigUint::from(1usize);
+
+        let sum = a.clone() + &b;
+        assert_eq!(sum.to_string(), "18446744073709551616");
+        assert_eq!(sum.clone() - &b, a);
+        assert_eq!(
+            (&sum * &sum).to_string(),
+            "340282366920938463463374607431768211456"
+        );
+        assert_eq!((&a * &BigUint::zero()), BigUint::zero());
+        assert_eq!((sum.clone() - sum).to_string(), "0");
+        assert_eq!(BigUint::from(1_000_000_000u64).to_string(), "1000000000");
+    }
+
+    #[test]
+    fn test_order_and_sum() {
+        let small = BigUint::from(999_999_999usize);
+        let large = BigUint::from(1_000_000_000usize);
+        assert!(small < large);
+        assert_eq!(
+            [small, large].iter().sum::<BigUint>().to_string(),
+            "1999999999"
+        );
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_negative() {
+        let _ = BigUint::from(1usize) - BigUint::from(2usize);
+    }
+}
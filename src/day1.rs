@@ -1,37 +1,272 @@
-use std::fs;
+use std::collections::VecDeque;
+use std::fmt::{self, Display};
 
-fn main() -> Result<(), Box<dyn std::error::Error + 'static>> {
-    let input = fs::read_to_string("inputs/day1")?;
+use crate::error::{parse_token, ParseError};
+use crate::Solution;
 
-    let values: Vec<_> = input.lines().map(|x| x.parse::<i32>().unwrap()).collect();
+/// Number of times the sum over a sliding `window` of depths increases
+///
+/// Two neighbouring windows share all but one depth each, so only the
+/// depth leaving and the one entering are compared, and just the last
+/// `window` depths are kept.
+pub fn count_increases<T: PartialOrd>(depths: impl IntoIterator<Item = T>, window: usize) -> usize {
+    assert!(window > 0, "a window holds at least one depth");
 
-    let mut sum = 0;
-    let mut iter = values.iter();
-    let mut current = iter.next().unwrap();
-    for num in iter {
-        if num > current {
-            sum += 1;
+    let mut last = VecDeque::with_capacity(window + 1);
+    let mut increases = 0;
+    for depth in depths {
+        last.push_back(depth);
+        if last.len() > window {
+            let leaving = last.pop_front().unwrap();
+            if *last.back().unwrap() > leaving {
+                increases += 1;
+            }
         }
-        current = num;
     }
-    println!("Answer Part one: '{}'", sum);
+    increases
+}
 
-    let len = values.len();
-    let mut iter = values[..len - 2]
-        .iter()
-        .zip(&values[1..len - 1])
-        .zip(&values[2..])
-        .map(|x| x.0 .0 + x.0 .1 + x.1);
-
-    let mut sum = 0;
-    let mut current = iter.next().unwrap();
-    for num in iter {
-        if num > current {
-            sum += 1;
+/// Consecutive depths, from `start` to `end` inclusive
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Run {
+    pub start: usize,
+    pub end: usize,
+}
+
+impl Run {
+    /// Number of depths in the run
+    pub fn depths(&self) -> usize {
+        self.end - self.start + 1
+    }
+}
+
+/// The largest change between two neighbouring depths, from `index` to `index + 1`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Jump {
+    pub index: usize,
+    /// Wider than the depths, which can be a full `i32` apart
+    pub change: i64,
+}
+
+/// Statistics of a depth curve
+#[derive(Debug, Clone, PartialEq)]
+pub struct Profile {
+    pub count: usize,
+    pub min: i32,
+    pub max: i32,
+    pub mean: f64,
+    pub median: f64,
+    /// Longest strictly increasing run, the first one on a tie
+    pub increasing: Run,
+    /// Longest strictly decreasing run, the first one on a tie
+    pub decreasing: Run,
+    /// `None` with a single depth
+    pub jump: Option<Jump>,
+}
+
+/// Longest run of neighbours for which `continues(previous, next)` holds
+fn longest_run(depths: &[i32], continues: impl Fn(i32, i32) -> bool) -> Run {
+    let mut longest = Run { start: 0, end: 0 };
+    let mut start = 0;
+    for (i, pair) in depths.windows(2).enumerate() {
+        if !continues(pair[0], pair[1]) {
+            start = i + 1;
+        }
+        let current = Run { start, end: i + 1 };
+        if current.depths() > longest.depths() {
+            longest = current;
         }
-        current = num;
     }
-    println!("Answer Part two: '{}'", sum);
+    longest
+}
+
+impl Profile {
+    /// `None` if there are no depths
+    pub fn new(depths: &[i32]) -> Option<Profile> {
+        let mut sorted = depths.to_vec();
+        sorted.sort_unstable();
+        let count = sorted.len();
+        let median = match count {
+            0 => return None,
+            n if n % 2 == 1 => sorted[n / 2] as f64,
+            n => (sorted[n / 2 - 1] as f64 + sorted[n / 2] as f64) / 2.0,
+        };
+
+        let jump = depths
+            .windows(2)
+            .enumerate()
+            .map(|(index, pair)| Jump {
+                index,
+                change: pair[1] as i64 - pair[0] as i64,
+            })
+            .rev()
+            .max_by_key(|j| j.change.abs());
+
+        Some(Profile {
+            count,
+            min: sorted[0],
+            max: sorted[count - 1],
+            mean: depths.iter().map(|&d| d as f64).sum::<f64>() / count as f64,
+            median,
+            increasing: longest_run(depths, |a, b| b > a),
+            decreasing: longest_run(depths, |a, b| b < a),
+            jump,
+        })
+    }
+}
+
+impl Display for Profile {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "depths:     {}", self.count)?;
+        writeln!(f, "min:        {}", self.min)?;
+        writeln!(f, "max:        {}", self.max)?;
+        writeln!(f, "mean:       {:.2}", self.mean)?;
+        writeln!(f, "median:     {:.1}", self.median)?;
+        for (name, run) in [
+            ("increasing", self.increasing),
+            ("decreasing", self.decreasing),
+        ] {
+            writeln!(
+                f,
+                "{}: {} depths, from {} to {}",
+                name,
+                run.depths(),
+                run.start,
+                run.end
+            )?;
+        }
+        match self.jump {
+            Some(jump) => writeln!(
+                f,
+                "jump:       {:+}, from {} to {}",
+                jump.change,
+                jump.index,
+                jump.index + 1
+            ),
+            None => writeln!(f, "jump:       none"),
+        }
+    }
+}
+
+/// The depth curve in at most `width` block characters, higher meaning deeper
+///
+/// With more depths than `width`, every character shows the mean of a
+/// range of neighbouring depths.
+pub fn sparkline(depths: &[i32], width: usize) -> String {
+    const BARS: [char; 8] = ['▁', '▂', '▃', '▄', '▅', '▆', '▇', '█'];
+
+    let columns = width.min(depths.len());
+    let means: Vec<f64> = (0..columns)
+        .map(|c| {
+            let bucket = &depths[c * depths.len() / columns..(c + 1) * depths.len() / columns];
+            bucket.iter().map(|&d| d as f64).sum::<f64>() / bucket.len() as f64
+        })
+        .collect();
+
+    let min = means.iter().copied().fold(f64::INFINITY, f64::min);
+    let max = means.iter().copied().fold(f64::NEG_INFINITY, f64::max);
+    means
+        .iter()
+        .map(|&m| match max > min {
+            true => BARS[((m - min) / (max - min) * 7.0).round() as usize],
+            false => BARS[0],
+        })
+        .collect()
+}
+
+pub struct Day1;
+
+impl Solution for Day1 {
+    const DAY: u8 = 1;
+
+    type Input = Vec<i32>;
+    type AnswerOne = usize;
+    type AnswerTwo = usize;
+
+    fn parse(input: &str) -> Result<Vec<i32>, ParseError> {
+        input
+            .lines()
+            .map(|x| parse_token(input, x, "a depth"))
+            .collect()
+    }
+
+    fn part_one(values: &Vec<i32>) -> usize {
+        count_increases(values, 1)
+    }
+
+    fn part_two(values: &Vec<i32>) -> usize {
+        count_increases(values, 3)
+    }
+}
 
-    Ok(())
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DEPTHS: &str = "199\n200\n208\n210\n200\n207\n240\n269\n260\n263\n";
+
+    #[test]
+    fn test_parts() {
+        let depths = Day1::parse(DEPTHS).unwrap();
+        assert_eq!(Day1::part_one(&depths), 7);
+        assert_eq!(Day1::part_two(&depths), 5);
+    }
+
+    #[test]
+    fn test_count_increases() {
+        // straight from the text, without collecting the depths
+        let stream = DEPTHS.lines().map(|l| l.parse::<i32>().unwrap());
+        assert_eq!(count_increases(stream, 3), 5);
+
+        assert_eq!(count_increases([1, 2, 3, 4], 2), 2);
+        assert_eq!(count_increases([1, 2, 3, 4], 4), 0);
+        assert_eq!(count_increases(Vec::<i32>::new(), 3), 0);
+        assert_eq!(Day1::part_two(&vec![1, 2]), 0);
+    }
+
+    #[test]
+    fn test_profile() {
+        let depths = Day1::parse(DEPTHS).unwrap();
+        let profile = Profile::new(&depths).unwrap();
+        assert_eq!((profile.min, profile.max), (199, 269));
+        assert_eq!(profile.mean, 225.6);
+        assert_eq!(profile.median, 209.0);
+        assert_eq!(profile.increasing, Run { start: 0, end: 3 });
+        assert_eq!(profile.decreasing, Run { start: 3, end: 4 });
+        assert_eq!(
+            profile.jump,
+            Some(Jump {
+                index: 5,
+                change: 33
+            })
+        );
+        assert!(profile
+            .to_string()
+            .contains("increasing: 4 depths, from 0 to 3\n"));
+
+        let single = Profile::new(&[3]).unwrap();
+        assert_eq!(single.increasing, Run { start: 0, end: 0 });
+        assert_eq!(single.jump, None);
+        assert_eq!(Profile::new(&[]), None);
+
+        let extremes = Profile::new(&[i32::MIN, i32::MAX, i32::MIN]).unwrap();
+        assert_eq!(
+            extremes.jump,
+            Some(Jump {
+                index: 0,
+                change: 4_294_967_295
+            })
+        );
+        assert!(extremes
+            .to_string()
+            .contains("jump:       +4294967295, from 0 to 1\n"));
+    }
+
+    #[test]
+    fn test_sparkline() {
+        assert_eq!(sparkline(&[0, 1, 2, 3, 4, 5, 6, 7], 8), "▁▂▃▄▅▆▇█");
+        assert_eq!(sparkline(&[0, 0, 7, 7], 2), "▁█");
+        assert_eq!(sparkline(&[5, 5], 10), "▁▁");
+        assert_eq!(sparkline(&[], 10), "");
+    }
 }
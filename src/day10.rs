@@ -1,26 +1,28 @@
-use std::fs;
+use crate::error::ParseError;
+use crate::Solution;
 
-#[derive(PartialEq, Eq)]
-enum Brace {
+#[derive(Debug, PartialEq, Eq)]
+pub enum Brace {
     Triangle,
     Round,
     Square,
     Curly,
 }
 
-enum Delimiter {
+#[derive(Debug)]
+pub enum Delimiter {
     Open(Brace),
     Close(Brace),
 }
 
 impl Brace {
-    fn from_char(c: char) -> Result<Self, String> {
+    fn from_char(c: char) -> Option<Self> {
         match c {
-            '<' | '>' => Ok(Self::Triangle),
-            '(' | ')' => Ok(Self::Round),
-            '[' | ']' => Ok(Self::Square),
-            '{' | '}' => Ok(Self::Curly),
-            _ => Err(format!("Unknown Delimiter {}", c)),
+            '<' | '>' => Some(Self::Triangle),
+            '(' | ')' => Some(Self::Round),
+            '[' | ']' => Some(Self::Square),
+            '{' | '}' => Some(Self::Curly),
+            _ => None,
         }
     }
 
@@ -46,11 +48,11 @@ impl Brace {
 }
 
 impl Delimiter {
-    fn from_char(c: char) -> Result<Self, String> {
+    fn from_char(c: char) -> Option<Self> {
         match c {
-            '<' | '(' | '[' | '{' => Ok(Self::Open(Brace::from_char(c)?)),
-            '>' | ')' | ']' | '}' => Ok(Self::Close(Brace::from_char(c)?)),
-            _ => Err(format!("Unknown Delimiter {}", c)),
+            '<' | '(' | '[' | '{' => Some(Self::Open(Brace::from_char(c)?)),
+            '>' | ')' | ']' | '}' => Some(Self::Close(Brace::from_char(c)?)),
+            _ => None,
         }
     }
 
@@ -69,78 +71,89 @@ impl Delimiter {
     }
 }
 
-fn part_one(filename: &str) -> usize {
-    fs::read_to_string(filename)
-        .unwrap()
-        .lines()
-        .filter_map(|f| {
-            use Delimiter::{Close, Open};
-            let mut stack: Vec<Delimiter> = Vec::new();
-
-            for c in f.chars() {
-                let d = Delimiter::from_char(c).unwrap();
-                match &d {
-                    Open(_) => stack.push(d),
-                    Close(c) => match stack.pop() {
-                        Some(o) => match &o {
-                            Open(p) if *p == *c => (),
-                            Close(_) => panic!("Close bracket on stack"),
-                            Open(_) => return Some(d.get_score()),
+pub struct Day10;
+
+impl Solution for Day10 {
+    const DAY: u8 = 10;
+
+    type Input = Vec<Vec<Delimiter>>;
+    type AnswerOne = usize;
+    type AnswerTwo = usize;
+
+    fn parse(input: &str) -> Result<Vec<Vec<Delimiter>>, ParseError> {
+        input
+            .lines()
+            .map(|l| {
+                l.char_indices()
+                    .map(|(i, c)| {
+                        Delimiter::from_char(c).ok_or_else(|| {
+                            ParseError::invalid(input, &l[i..i + c.len_utf8()], "a brace")
+                        })
+                    })
+                    .collect()
+            })
+            .collect()
+    }
+
+    fn part_one(lines: &Vec<Vec<Delimiter>>) -> usize {
+        lines
+            .iter()
+            .filter_map(|f| {
+                use Delimiter::{Close, Open};
+                let mut stack: Vec<&Delimiter> = Vec::new();
+
+                for d in f {
+                    match d {
+                        Open(_) => stack.push(d),
+                        Close(c) => match stack.pop() {
+                            Some(o) => match o {
+                                Open(p) if *p == *c => (),
+                                Close(_) => panic!("Close bracket on stack"),
+                                Open(_) => return Some(d.get_score()),
+                            },
+                            None => return None,
                         },
-                        None => return None,
-                    },
+                    }
                 }
-            }
-            None
-        })
-        .sum()
-}
+                None
+            })
+            .sum()
+    }
 
-fn part_two(filename: &str) -> usize {
-    let mut scores: Vec<_> = fs::read_to_string(filename)
-        .unwrap()
-        .lines()
-        .filter_map(|f| {
-            use Delimiter::{Close, Open};
-            let mut stack: Vec<Delimiter> = Vec::new();
-
-            for c in f.chars() {
-                let d = Delimiter::from_char(c).unwrap();
-                match &d {
-                    Open(_) => stack.push(d),
-                    Close(c) => match stack.pop() {
-                        Some(o) => match &o {
-                            Open(p) if *p == *c => (),
-                            Close(_) => panic!("Close bracket on stack"),
-                            Open(_) => return None,
+    fn part_two(lines: &Vec<Vec<Delimiter>>) -> usize {
+        let mut scores: Vec<_> = lines
+            .iter()
+            .filter_map(|f| {
+                use Delimiter::{Close, Open};
+                let mut stack: Vec<&Delimiter> = Vec::new();
+
+                for d in f {
+                    match d {
+                        Open(_) => stack.push(d),
+                        Close(c) => match stack.pop() {
+                            Some(o) => match o {
+                                Open(p) if *p == *c => (),
+                                Close(_) => panic!("Close bracket on stack"),
+                                Open(_) => return None,
+                            },
+                            None => return None,
                         },
-                        None => return None,
-                    },
+                    }
                 }
-            }
 
-            let mut score = 0usize;
-            for d in stack.iter().rev() {
-                score *= 5;
-                score += d.get_score_two();
-            }
-
-            Some(score)
-        })
-        .collect();
-
-    scores.sort_unstable();
-    scores[scores.len() / 2]
-}
-
-fn main() -> Result<(), Box<dyn std::error::Error + 'static>> {
-    let res = part_one("inputs/day10");
-    println!("Answer part one: {}", res);
+                let mut score = 0usize;
+                for d in stack.iter().rev() {
+                    score *= 5;
+                    score += d.get_score_two();
+                }
 
-    let res = part_two("inputs/day10");
-    println!("Answer part two: {}", res);
+                Some(score)
+            })
+            .collect();
 
-    Ok(())
+        scores.sort_unstable();
+        scores[scores.len() / 2]
+    }
 }
 
 #[cfg(test)]
@@ -149,13 +162,21 @@ mod tests {
 
     #[test]
     fn test_part_one() {
-        let res = part_one("inputs/day10_test");
+        let lines = Day10::parse_file("inputs/day10_test").unwrap();
+        let res = Day10::part_one(&lines);
         assert_eq!(res, 26397);
     }
 
     #[test]
     fn test_part_two() {
-        let res = part_two("inputs/day10_test");
+        let lines = Day10::parse_file("inputs/day10_test").unwrap();
+        let res = Day10::part_two(&lines);
         assert_eq!(res, 288957);
     }
+
+    #[test]
+    fn test_parse_error() {
+        let err = Day10::parse("[({(<(())[]>[[{[]{<()<>>\n[(()[<>])]({[<{<a>>{{\n").unwrap_err();
+        assert_eq!(err.to_string(), "<input>:2:17: expected a brace, found 'a'");
+    }
 }
@@ -1,152 +1,82 @@
-use std::fmt::Display;
-use std::{fs, io};
-
-#[derive(Debug, Clone)]
-struct Field {
-    numbers: Vec<u8>,
-    pub rows: usize,
-    pub cols: usize,
+use crate::error::ParseError;
+use crate::grid::Grid;
+use crate::Solution;
+
+pub fn n_step(field: &mut Grid<u8>, n: usize) -> usize {
+    let mut sum = 0;
+    for _ in 0..n {
+        sum += step(field);
+    }
+    sum
 }
 
-impl Field {
-    fn from_str(s: &str) -> Field {
-        let numbers = s
-            .replace("\n", "")
-            .chars()
-            .map(|c| c.to_digit(10).unwrap() as u8)
-            .collect();
-        let rows = s.lines().count();
-        let cols = s.lines().next().unwrap().len();
-
-        Field {
-            numbers,
-            rows,
-            cols,
-        }
+/// Advances the octopuses by one step and returns the number of flashes
+pub fn step(field: &mut Grid<u8>) -> usize {
+    for o in field.iter_mut() {
+        *o += 1;
     }
 
-    fn get(&mut self, x: isize, y: isize) -> Option<&mut u8> {
-        if x < self.cols as isize && 0 <= x && y < self.rows as isize && 0 <= y {
-            Some(&mut self.numbers[self.cols * y as usize + x as usize])
-        } else {
-            None
+    let mut que: Vec<_> = field
+        .iter()
+        .enumerate()
+        .filter_map(|(idx, &x)| match x == 10 {
+            false => None,
+            true => Some(field.get_coord(idx)),
+        })
+        .collect();
+
+    let mut flashes = 0;
+    while let Some(coord) = que.pop() {
+        let val = &mut field[coord];
+
+        if *val < 10 {
+            *val += 1;
         }
-    }
-
-    fn get_coord(&self, index: usize) -> (isize, isize) {
-        (
-            index as isize % self.cols as isize,
-            index as isize / self.cols as isize,
-        )
-    }
 
-    fn n_step(&mut self, n: usize) -> usize {
-        let mut sum = 0;
-        for _ in 0..n {
-            sum += self.step();
+        if *val == 10 {
+            *val += 1;
+            que.extend(field.neighbours8(coord));
         }
-        sum
     }
 
-    fn step(&mut self) -> usize {
-        for o in &mut self.numbers {
-            *o += 1;
+    for o in field.iter_mut() {
+        if *o >= 10 {
+            *o = 0;
+            flashes += 1;
         }
-
-        let mut que: Vec<_> = self
-            .numbers
-            .iter()
-            .enumerate()
-            .filter_map(|(idx, &x)| match x == 10 {
-                false => None,
-                true => Some(self.get_coord(idx)),
-            })
-            .collect();
-
-        let mut flashes = 0;
-        loop {
-            let coord = match que.pop() {
-                Some(p) => p,
-                None => break,
-            };
-
-            let val = match self.get(coord.0, coord.1) {
-                Some(x) => x,
-                None => continue,
-            };
-
-            if *val < 10 {
-                *val += 1;
-            }
-
-            if *val == 10 {
-                *val += 1;
-                for (dx, dy) in [
-                    (0isize, 1isize),
-                    (0, -1),
-                    (1, 0),
-                    (-1, 0),
-                    (1, 1),
-                    (1, -1),
-                    (-1, -1),
-                    (-1, 1),
-                ] {
-                    que.push((coord.0 + dx, coord.1 + dy));
-                }
-            }
-        }
-
-        for o in &mut self.numbers {
-            if *o >= 10 {
-                *o = 0;
-                flashes += 1;
-            }
-        }
-
-        flashes
     }
-}
-
-impl Display for Field {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        let s: String = self
-            .numbers
-            .chunks(self.cols)
-            .map(|x| x.iter().map(|x| format!("{}", x)).collect::<String>() + "\n")
-            .collect();
 
-        write!(f, "{}", s)
-    }
+    flashes
 }
 
-fn parse(filename: &str) -> io::Result<Field> {
-    let file = fs::read_to_string(filename)?;
+pub struct Day11;
 
-    Ok(Field::from_str(&file))
-}
+impl Solution for Day11 {
+    const DAY: u8 = 11;
 
-fn part_one(field: &Field) -> usize {
-    let mut priv_field = field.clone();
-    priv_field.n_step(100)
-}
+    /// Energy levels of the octopuses
+    type Input = Grid<u8>;
+    type AnswerOne = usize;
+    type AnswerTwo = usize;
 
-fn part_two(field: &Field) -> usize {
-    let mut priv_field = field.clone();
-    let len = priv_field.numbers.len();
-    let mut step = 1;
-    while priv_field.step() < len {
-        step += 1;
+    fn parse(input: &str) -> Result<Grid<u8>, ParseError> {
+        Grid::from_digits(input)
     }
-    step
-}
-
-fn main() -> Result<(), Box<dyn std::error::Error + 'static>> {
-    let field = parse("inputs/day11").unwrap();
 
-    println!("Answer part one: {}", part_one(&field));
-    println!("Answer part two: {}", part_two(&field));
+    fn part_one(field: &Grid<u8>) -> usize {
+        let mut priv_field = field.clone();
+        n_step(&mut priv_field, 100)
+    }
 
-    Ok(())
+    fn part_two(field: &Grid<u8>) -> usize {
+        let mut priv_field = field.clone();
+        let len = priv_field.len();
+        let mut n = 1;
+        while step(&mut priv_field) < len {
+            n += 1;
+        }
+        n
+    }
 }
 
 #[cfg(test)]
@@ -155,34 +85,34 @@ mod tests {
 
     #[test]
     fn test_input() {
-        let field = parse("inputs/day11_test").unwrap();
+        let field = Day11::parse_file("inputs/day11_test").unwrap();
         println!("{}", field);
-        assert_eq!(field.cols, 10);
-        assert_eq!(field.rows, 10);
-        assert_eq!(field.numbers[0], 5);
+        assert_eq!(field.width(), 10);
+        assert_eq!(field.height(), 10);
+        assert_eq!(field[(0, 0)], 5);
     }
 
     #[test]
     fn test_part_one() {
-        let mut field = parse("inputs/day11_test").unwrap();
-        let mut test = field.step();
+        let mut field = Day11::parse_file("inputs/day11_test").unwrap();
+        let mut test = step(&mut field);
         println!("{}", field);
-        test += field.step();
+        test += step(&mut field);
         println!("{}", field);
-        test += field.n_step(18);
+        test += n_step(&mut field, 18);
         println!("{}", field);
-        assert_eq!(field.n_step(80) + test, 1656);
+        assert_eq!(n_step(&mut field, 80) + test, 1656);
     }
 
     #[test]
     fn test_part_two() {
-        let field = parse("inputs/day11_test").unwrap();
-        assert_eq!(part_two(&field), 195);
+        let field = Day11::parse_file("inputs/day11_test").unwrap();
+        assert_eq!(Day11::part_two(&field), 195);
     }
 
     #[test]
     fn test_pos() {
-        let field = parse("inputs/day11_test").unwrap();
+        let field = Day11::parse_file("inputs/day11_test").unwrap();
         println!("{}", field);
         assert_eq!(field.get_coord(10), (0, 1));
         assert_eq!(field.get_coord(0), (0, 0));
@@ -1,6 +1,7 @@
 use std::collections::{HashMap, HashSet};
-use std::fs;
-use std::io;
+
+use crate::error::ParseError;
+use crate::Solution;
 
 #[derive(PartialEq)]
 enum NodeType {
@@ -8,11 +9,13 @@ enum NodeType {
     Lower,
 }
 
-struct Node {
+pub struct Node {
     connections: HashSet<String>,
     ntype: NodeType,
 }
 
+pub type Graph = HashMap<String, Node>;
+
 #[derive(Clone)]
 struct Route {
     path: Vec<String>,
@@ -37,130 +40,130 @@ impl Node {
     }
 }
 
-fn parse(filename: &str) -> io::Result<HashMap<String, Node>> {
-    let mut graph = HashMap::new();
-    for line in fs::read_to_string(filename)?.lines() {
-        let names: Vec<_> = line.split("-").collect();
-        for (i, j) in [(0, 1), (1, 0)] {
-            // this 'if' is only relevant for part two
-            // consider this dirty
-            if names[j] != "start" {
-                match graph.get_mut(names[i]) {
-                    None => {
-                        let mut node = Node::from_str(names[i]);
-                        node.connections.insert(names[j].to_string());
-                        graph.insert(names[i].to_string(), node);
-                    }
-                    Some(x) => {
-                        x.connections.insert(names[j].to_string());
-                    }
+pub struct Day12;
+
+impl Solution for Day12 {
+    const DAY: u8 = 12;
+
+    type Input = Graph;
+    type AnswerOne = usize;
+    type AnswerTwo = usize;
+
+    fn parse(input: &str) -> Result<Graph, ParseError> {
+        let mut graph = HashMap::new();
+        for line in input.lines() {
+            let (from, to) = line
+                .split_once("-")
+                .ok_or_else(|| ParseError::missing(input, line, "'-' and a cave"))?;
+            let names = [from, to];
+            for (i, j) in [(0, 1), (1, 0)] {
+                // every cave is a node, even if its only way is back to start
+                let node = graph
+                    .entry(names[i].to_string())
+                    .or_insert_with(|| Node::from_str(names[i]));
+                // this 'if' is only relevant for part two
+                // consider this dirty
+                if names[j] != "start" {
+                    node.connections.insert(names[j].to_string());
                 }
             }
         }
-    }
-
-    Ok(graph)
-}
 
-fn part_two(graph: &HashMap<String, Node>) -> usize {
-    let mut routes: Vec<Route> = vec![Route {
-        path: vec![String::from("start")],
-        doubled: false,
-    }];
+        for cave in ["start", "end"] {
+            if !graph.contains_key(cave) {
+                return Err(ParseError::missing(
+                    input,
+                    input,
+                    format!("a cave '{}'", cave),
+                ));
+            }
+        }
 
-    let mut finished_routes: Vec<_> = Vec::new();
+        Ok(graph)
+    }
 
-    loop {
-        let mut current_route = match routes.pop() {
-            Some(x) => x,
-            None => break,
-        };
+    fn part_one(graph: &Graph) -> usize {
+        let mut routes: Vec<Vec<String>> = vec![vec![String::from("start")]];
 
-        let current_name = current_route.path.last().unwrap();
-        let current_node = graph.get(current_name).unwrap();
+        let mut finished_routes: Vec<Vec<String>> = Vec::new();
 
-        if current_name == "end" {
-            finished_routes.push(current_route);
-            continue;
-        }
+        while let Some(current_route) = routes.pop() {
+            let current_name = current_route.last().unwrap();
+            let current_node = graph.get(current_name).unwrap();
 
-        match current_node.ntype {
-            NodeType::Lower => {
-                if current_route
-                    .path
-                    .iter()
-                    .filter(|&x| (*current_name).eq(x))
-                    .count()
-                    > 1
-                {
-                    if current_route.doubled {
+            match current_node.ntype {
+                NodeType::Lower => {
+                    if current_route
+                        .iter()
+                        .filter(|&x| (*current_name).eq(x))
+                        .count()
+                        > 1
+                    {
                         continue;
-                    } else {
-                        current_route.doubled = true;
                     }
                 }
+                NodeType::Upper => (),
             }
-            NodeType::Upper => (),
-        }
 
-        for next in &current_node.connections {
-            let mut add = current_route.clone();
-            add.path.push(next.to_string());
-            routes.push(add);
+            for next in &current_node.connections {
+                let mut add = current_route.clone();
+                add.push(next.to_string());
+                routes.push(add);
+            }
+
+            if current_name == "end" {
+                finished_routes.push(current_route);
+            }
         }
+
+        finished_routes.len()
     }
 
-    finished_routes.len()
-}
+    fn part_two(graph: &Graph) -> usize {
+        let mut routes: Vec<Route> = vec![Route {
+            path: vec![String::from("start")],
+            doubled: false,
+        }];
+
+        let mut finished_routes: Vec<_> = Vec::new();
+
+        while let Some(mut current_route) = routes.pop() {
+            let current_name = current_route.path.last().unwrap();
+            let current_node = graph.get(current_name).unwrap();
+
+            if current_name == "end" {
+                finished_routes.push(current_route);
+                continue;
+            }
 
-fn part_one(graph: &HashMap<String, Node>) -> usize {
-    let mut routes: Vec<Vec<String>> = vec![vec![String::from("start")]];
-
-    let mut finished_routes: Vec<Vec<String>> = Vec::new();
-
-    loop {
-        let current_route = match routes.pop() {
-            Some(x) => x,
-            None => break,
-        };
-
-        let current_name = current_route.last().unwrap();
-        let current_node = graph.get(current_name).unwrap();
-
-        match current_node.ntype {
-            NodeType::Lower => {
-                if current_route
-                    .iter()
-                    .filter(|&x| (*current_name).eq(x))
-                    .count()
-                    > 1
-                {
-                    continue;
+            match current_node.ntype {
+                NodeType::Lower => {
+                    if current_route
+                        .path
+                        .iter()
+                        .filter(|&x| (*current_name).eq(x))
+                        .count()
+                        > 1
+                    {
+                        if current_route.doubled {
+                            continue;
+                        } else {
+                            current_route.doubled = true;
+                        }
+                    }
                 }
+                NodeType::Upper => (),
             }
-            NodeType::Upper => (),
-        }
 
-        for next in &current_node.connections {
-            let mut add = current_route.clone();
-            add.push(next.to_string());
-            routes.push(add);
+            for next in &current_node.connections {
+                let mut add = current_route.clone();
+                add.path.push(next.to_string());
+                routes.push(add);
+            }
         }
 
-        if current_name == "end" {
-            finished_routes.push(current_route);
-        }
+        finished_routes.len()
     }
-
-    finished_routes.len()
-}
-
-fn main() -> Result<(), Box<dyn std::error::Error + 'static>> {
-    let graph = parse("inputs/day12").unwrap();
-    println!("Answer Part One: {} ", part_one(&graph));
-
-    println!("Answer Part Two: {} ", part_two(&graph));
-    Ok(())
 }
 
 #[cfg(test)]
@@ -169,7 +172,7 @@ mod tests {
 
     #[test]
     fn test_input() {
-        let r = parse("inputs/day12_test").unwrap();
+        let r = Day12::parse_file("inputs/day12_test").unwrap();
         assert!(r["HN"].ntype == NodeType::Upper);
         assert!(r["dc"].ntype == NodeType::Lower);
         assert!(r.contains_key("start"));
@@ -180,13 +183,26 @@ mod tests {
 
     #[test]
     fn test_part_one() {
-        let graph = parse("inputs/day12_test").unwrap();
-        assert_eq!(part_one(&graph), 19)
+        let graph = Day12::parse_file("inputs/day12_test").unwrap();
+        assert_eq!(Day12::part_one(&graph), 19)
     }
 
     #[test]
     fn test_part_two() {
-        let graph = parse("inputs/day12_test").unwrap();
-        assert_eq!(part_two(&graph), 103)
+        let graph = Day12::parse_file("inputs/day12_test").unwrap();
+        assert_eq!(Day12::part_two(&graph), 103)
+    }
+
+    #[test]
+    fn test_dead_ends() {
+        // x only leads back to start, A never reaches start
+        let graph = Day12::parse("start-x\nstart-end\nA-end").unwrap();
+        assert!(graph["x"].connections.is_empty());
+        assert_eq!(Day12::part_one(&graph), 1);
+        assert_eq!(Day12::part_two(&graph), 1);
+
+        let graph = Day12::parse("start-end").unwrap();
+        assert_eq!(Day12::part_one(&graph), 1);
+        assert_eq!(Day12::part_two(&graph), 1);
     }
 }
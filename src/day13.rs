@@ -1,194 +1,344 @@
-use std::fmt::Display;
-use std::ops::{Index, IndexMut};
-use std::{fs, io};
+use std::collections::HashSet;
+use std::error::Error;
+use std::fmt::{self, Display};
 
-#[derive(PartialEq, Debug)]
-enum Fold {
+use crate::error::{parse_token, ParseError};
+use crate::grid::Grid;
+use crate::ocr::{self, OcrError};
+use crate::Solution;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Fold {
     X(usize),
     Y(usize),
 }
 
 impl Fold {
-    fn from_str(s: &str) -> Fold {
-        let mut iter = s.chars().skip(11);
-        let dir = iter.next().unwrap();
-        iter.next();
+    /// s in the form 'fold along x=5', a part of `input`
+    fn from_str(input: &str, s: &str) -> Result<Fold, ParseError> {
+        let fold = s
+            .strip_prefix("fold along ")
+            .ok_or_else(|| ParseError::invalid(input, s, "'fold along'"))?;
+        let (dir, pos) = fold
+            .split_once("=")
+            .ok_or_else(|| ParseError::missing(input, fold, "'='"))?;
+        let pos = parse_token(input, pos, "a coordinate")?;
+
         match dir {
-            'x' => Self::X(iter.collect::<String>().parse().unwrap()),
-            'y' => Self::Y(iter.collect::<String>().parse().unwrap()),
-            c => panic!("unkown fold: {}", c),
+            "x" => Ok(Self::X(pos)),
+            "y" => Ok(Self::Y(pos)),
+            _ => Err(ParseError::invalid(input, dir, "x or y")),
+        }
+    }
+}
+
+impl Display for Fold {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Fold::X(col) => write!(f, "fold along x={}", col),
+            Fold::Y(row) => write!(f, "fold along y={}", row),
         }
     }
 }
 
-#[derive(Debug, Clone)]
-struct Field<T> {
-    content: Vec<T>,
-    pub rows: usize,
-    pub cols: usize,
+/// A fold whose line goes through a dot
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FoldError {
+    pub fold: Fold,
+    pub dot: (usize, usize),
+}
+
+impl Display for FoldError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "the {} goes through the dot at {},{}",
+            self.fold, self.dot.0, self.dot.1
+        )
+    }
+}
+
+impl Error for FoldError {}
+
+/// The folded dots are no known letters
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Unreadable {
+    pub error: OcrError,
+    /// The dots as '#' to read them by eye, if the paper is letter-sized
+    pub picture: Option<String>,
 }
 
-impl Field<bool> {
-    fn new(rows: usize, cols: usize) -> Field<bool> {
-        Field {
-            content: vec![false; rows * cols],
-            rows,
-            cols,
+impl Display for Unreadable {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.error)?;
+        match &self.picture {
+            Some(picture) => write!(f, "\nthe folded dots:\n{}", picture.trim_end_matches('\n')),
+            None => Ok(()),
         }
     }
+}
 
-    fn from_str(s: &str) -> Field<bool> {
-        let points: Vec<_> = s
-            .lines()
-            .map(|x| {
-                let mut iter = x.split(",").map(|x| x.parse::<usize>().unwrap());
-                let x = iter.next().unwrap();
-                let y = iter.next().unwrap();
-                assert!(iter.next().is_none());
+impl Error for Unreadable {}
 
-                (x, y)
-            })
-            .collect();
+/// Most cells a paper can have to be exported, a 64 MB text picture
+const MAX_EXPORT_CELLS: usize = 1 << 26;
 
-        let cols = points.iter().map(|f| f.0).max().unwrap() + 1;
-        let rows = points.iter().map(|f| f.1).max().unwrap() + 1;
+/// The paper is too large to be exported as a picture
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TooLarge {
+    pub width: usize,
+    pub height: usize,
+}
+
+impl Display for TooLarge {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "sheet {}x{} too large to export",
+            self.width, self.height
+        )
+    }
+}
 
-        let content = vec![false; cols * rows];
+impl Error for TooLarge {}
+
+/// Transparent paper with dots on it
+///
+/// Only the dots are stored, so folding takes time and memory
+/// proportional to their number, however large the paper is.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Paper {
+    dots: HashSet<(usize, usize)>,
+    width: usize,
+    height: usize,
+}
 
-        let mut f = Field {
-            content,
-            rows,
-            cols,
-        };
+impl Paper {
+    /// Parses the dots in `s`, a part of `input`
+    fn from_str(input: &str, s: &str) -> Result<Paper, ParseError> {
+        let dots: HashSet<_> = s
+            .lines()
+            .map(|line| -> Result<_, ParseError> {
+                let (x, y) = line
+                    .split_once(",")
+                    .ok_or_else(|| ParseError::missing(input, line, "',y'"))?;
+
+                Ok((
+                    parse_token::<usize>(input, x, "a coordinate")?,
+                    parse_token::<usize>(input, y, "a coordinate")?,
+                ))
+            })
+            .collect::<Result<_, _>>()?;
 
-        for p in points {
-            f[p] = true;
+        if dots.is_empty() {
+            return Err(ParseError::missing(input, s, "a dot"));
         }
 
-        f
+        let width = dots.iter().map(|d| d.0).max().unwrap() + 1;
+        let height = dots.iter().map(|d| d.1).max().unwrap() + 1;
+
+        Ok(Paper {
+            dots,
+            width,
+            height,
+        })
     }
 
-    fn folded(&self, fold: &Fold) -> Field<bool> {
-        match fold {
-            Fold::X(col) => self.folded_x(*col),
-            Fold::Y(row) => self.folded_y(*row),
-        }
+    pub fn width(&self) -> usize {
+        self.width
     }
 
-    fn folded_y(&self, row: usize) -> Field<bool> {
-        let mut flipped = Field::new(row, self.cols);
-        for x in 0..self.cols {
-            for y in 0..row {
-                flipped[(x, y)] = self[(x, y)] || self[(x, 2 * row - y)];
-            }
-        }
+    pub fn height(&self) -> usize {
+        self.height
+    }
 
-        flipped
+    pub fn contains(&self, pos: (usize, usize)) -> bool {
+        self.dots.contains(&pos)
     }
 
-    fn folded_x(&self, col: usize) -> Field<bool> {
-        let mut flipped = Field::new(self.rows, col);
-        for x in 0..col {
-            for y in 0..self.rows {
-                flipped[(x, y)] = self[(x, y)] || self[(2 * col - x, y)];
-            }
+    /// Number of dots, overlapping dots count once
+    pub fn count(&self) -> usize {
+        self.dots.len()
+    }
+
+    /// The paper folded up (y) or left (x) along the line `fold`
+    ///
+    /// The line can be anywhere, even outside of the paper. The folded
+    /// paper is as large as the larger side, with the smaller side moved
+    /// to line up with the fold.
+    pub fn folded(&self, fold: &Fold) -> Result<Paper, FoldError> {
+        let (line, size) = match *fold {
+            Fold::X(col) => (col, self.width),
+            Fold::Y(row) => (row, self.height),
+        };
+        // size of the larger side, dots next to the line end up at new_size - 1
+        let new_size = line.max(size.saturating_sub(line + 1));
+        let mirror = |pos: usize| match pos < line {
+            true => new_size - line + pos,
+            false => new_size + line - pos,
+        };
+
+        let mut dots = HashSet::with_capacity(self.dots.len());
+        for &(x, y) in &self.dots {
+            let dot = match *fold {
+                Fold::X(col) if x == col => None,
+                Fold::Y(row) if y == row => None,
+                Fold::X(_) => Some((mirror(x), y)),
+                Fold::Y(_) => Some((x, mirror(y))),
+            };
+            match dot {
+                Some(dot) => dots.insert(dot),
+                None => {
+                    return Err(FoldError {
+                        fold: fold.clone(),
+                        dot: (x, y),
+                    })
+                }
+            };
         }
 
-        flipped
+        let (width, height) = match *fold {
+            Fold::X(_) => (new_size, self.height),
+            Fold::Y(_) => (self.width, new_size),
+        };
+        Ok(Paper {
+            dots,
+            width,
+            height,
+        })
     }
 
-    fn count(&self) -> usize {
-        self.content
+    /// The paper after all of `folds`
+    pub fn folded_all(&self, folds: &[Fold]) -> Result<Paper, FoldError> {
+        folds
             .iter()
-            .map(|x| match x {
-                true => 1,
-                false => 0,
-            })
-            .sum()
+            .try_fold(self.clone(), |paper, fold| paper.folded(fold))
     }
-}
 
-impl<T> Field<T> {
-    #[allow(dead_code)]
-    fn get_coord(&self, index: usize) -> (usize, usize) {
-        (index % self.cols, index / self.cols)
+    /// The paper before any and after each of `folds`
+    pub fn stages(&self, folds: &[Fold]) -> Result<Vec<Paper>, FoldError> {
+        let mut stages = vec![self.clone()];
+        for fold in folds {
+            let next = stages.last().unwrap().folded(fold)?;
+            stages.push(next);
+        }
+        Ok(stages)
     }
 
-    fn get_index(&self, pos: (usize, usize)) -> usize {
-        self.cols * pos.1 + pos.0
+    /// Fails if the paper has more than [`MAX_EXPORT_CELLS`] cells
+    fn check_export(&self) -> Result<(), TooLarge> {
+        match self.width.checked_mul(self.height) {
+            Some(cells) if cells <= MAX_EXPORT_CELLS => Ok(()),
+            _ => Err(TooLarge {
+                width: self.width,
+                height: self.height,
+            }),
+        }
     }
-}
 
-impl Display for Field<bool> {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        let s: String = self
-            .content
-            .chunks(self.cols)
-            .map(|x| {
-                x.iter()
-                    .map(|x| match x {
-                        true => '#',
-                        false => ' ',
-                    })
-                    .collect::<String>()
-                    + "\n"
-            })
-            .collect();
-
-        write!(f, "{}", s.replace("0", "."))
+    /// Text picture of the paper, with '#' for dots and '.' for empty space
+    pub fn to_text(&self) -> Result<String, TooLarge> {
+        self.check_export()?;
+        let grid = self.to_grid().map(|&x| match x {
+            true => '#',
+            false => '.',
+        });
+        Ok(grid.to_string())
     }
-}
 
-impl<T> Index<(usize, usize)> for Field<T> {
-    type Output = T;
+    /// Binary (P4) PBM image of the paper, with a black pixel for every dot
+    pub fn to_pbm(&self) -> Result<Vec<u8>, TooLarge> {
+        self.check_export()?;
+        let mut pbm = format!("P4\n{} {}\n", self.width, self.height).into_bytes();
+        // every row starts on a new byte
+        let row_bytes = self.width.div_ceil(8);
+        let start = pbm.len();
+        pbm.resize(start + row_bytes * self.height, 0);
+        for &(x, y) in &self.dots {
+            pbm[start + y * row_bytes + x / 8] |= 0x80 >> (x % 8);
+        }
+        Ok(pbm)
+    }
 
-    fn index(&self, pos: (usize, usize)) -> &Self::Output {
-        &self.content[self.get_index(pos)]
+    /// Dense grid of the paper, `true` being a dot
+    pub fn to_grid(&self) -> Grid<bool> {
+        let mut grid = Grid::new(self.width, self.height, false);
+        for &dot in &self.dots {
+            grid[dot] = true;
+        }
+        grid
     }
 }
 
-impl<T> IndexMut<(usize, usize)> for Field<T> {
-    fn index_mut(&mut self, pos: (usize, usize)) -> &mut Self::Output {
-        let index = self.get_index(pos);
-        &mut self.content[index]
-    }
+/// Dots as '#', empty space as ' '
+fn render(paper: &Paper) -> Grid<char> {
+    paper.to_grid().map(|&x| match x {
+        true => '#',
+        false => ' ',
+    })
 }
 
-fn parse(filename: &str) -> io::Result<(Field<bool>, Vec<Fold>)> {
-    let file = fs::read_to_string(filename)?;
-    let mut parts = file.split("\n\n");
+pub struct Day13;
 
-    let field = Field::from_str(parts.next().unwrap());
-    let folds = parts
-        .next()
-        .unwrap()
-        .lines()
-        .map(|x| Fold::from_str(x))
-        .collect();
+impl Solution for Day13 {
+    const DAY: u8 = 13;
 
-    Ok((field, folds))
-}
+    /// The dotted paper and the folds to apply
+    type Input = (Paper, Vec<Fold>);
+    type AnswerOne = usize;
+    /// The code the dots spell out
+    type AnswerTwo = Result<String, Unreadable>;
 
-fn part_one(field: &Field<bool>, folds: &Vec<Fold>) -> usize {
-    field.folded(&folds[0]).count()
-}
+    fn parse(input: &str) -> Result<(Paper, Vec<Fold>), ParseError> {
+        let (dots, folds) = input
+            .split_once("\n\n")
+            .ok_or_else(|| ParseError::missing(input, input, "an empty line and folds"))?;
+
+        let paper = Paper::from_str(input, dots)?;
+        let lines: Vec<_> = folds.lines().collect();
+        let folds: Vec<_> = lines
+            .iter()
+            .map(|x| Fold::from_str(input, x))
+            .collect::<Result<_, _>>()?;
 
-fn part_two(field: &Field<bool>, folds: &Vec<Fold>) {
-    let mut iter = folds.iter();
-    let mut new = field.folded(iter.next().unwrap());
-    while let Some(fold) = iter.next() {
-        new = new.folded(fold);
+        if folds.is_empty() {
+            return Err(ParseError::missing(input, input, "a fold"));
+        }
+
+        // no fold may go through a dot, which only shows after the folds before it
+        let mut folded = paper.clone();
+        for (line, fold) in lines.iter().zip(&folds) {
+            folded = folded.folded(fold).map_err(|e| {
+                let expected = format!("a fold not through the dot at {},{}", e.dot.0, e.dot.1);
+                ParseError::invalid(input, line, expected)
+            })?;
+        }
+
+        Ok((paper, folds))
     }
-    println!("{}", new);
-}
 
-fn main() -> Result<(), Box<dyn std::error::Error + 'static>> {
-    let (field, folds) = parse("inputs/day13").unwrap();
-    println!("Answer part one: {}", part_one(&field, &folds));
-    println!("Answer part two:");
-    part_two(&field, &folds);
+    fn part_one((paper, folds): &(Paper, Vec<Fold>)) -> usize {
+        let folded = paper.folded(&folds[0]);
+        folded.expect("folds are checked when parsing").count()
+    }
 
-    Ok(())
+    fn part_two((paper, folds): &(Paper, Vec<Fold>)) -> Result<String, Unreadable> {
+        let folded = paper
+            .folded_all(folds)
+            .expect("folds are checked when parsing");
+
+        // the paper can be huge, so it only becomes a grid if it fits letters
+        if let Err(error) = ocr::check_size(folded.width(), folded.height()) {
+            return Err(Unreadable {
+                error,
+                picture: None,
+            });
+        }
+        ocr::read(&folded.to_grid()).map_err(|error| Unreadable {
+            error,
+            picture: Some(render(&folded).to_string()),
+        })
+    }
 }
 
 #[cfg(test)]
@@ -197,18 +347,193 @@ mod tests {
 
     #[test]
     fn test_input() {
-        let (field, folds) = parse("inputs/day13_test").unwrap();
+        let (paper, folds) = Day13::parse_file("inputs/day13_test").unwrap();
         assert_eq!(folds[0], Fold::Y(7));
         assert_eq!(folds[1], Fold::X(5));
-        assert_eq!(field[(6, 10)], true);
+        assert!(paper.contains((6, 10)));
+        assert_eq!(paper.count(), 18);
+        assert_eq!((paper.width(), paper.height()), (11, 15));
+    }
+
+    #[test]
+    fn test_parse_error() {
+        let err = Day13::parse("6,10\n0,14\n\nfold along z=7\n").unwrap_err();
+        assert_eq!(err.to_string(), "<input>:4:12: expected x or y, found 'z'");
     }
 
     #[test]
     fn test_part_one() {
-        let (field, folds) = parse("inputs/day13_test").unwrap();
-        println!("{}", field);
-        println!("");
-        println!("{}", field.folded_y(7));
-        assert_eq!(part_one(&field, &folds), 17);
+        let input = Day13::parse_file("inputs/day13_test").unwrap();
+        let paper = &input.0;
+        println!("{}", render(paper));
+        println!();
+        println!("{}", render(&paper.folded(&Fold::Y(7)).unwrap()));
+        assert_eq!(Day13::part_one(&input), 17);
+    }
+
+    #[test]
+    fn test_sparse() {
+        // far too large for a dense grid
+        let input =
+            "0,0\n4000000000,0\n0,2000000000\n\nfold along y=1000000000\nfold along x=2000000000\n";
+        let (paper, folds) = Day13::parse(input).unwrap();
+        assert_eq!(Day13::part_one(&(paper.clone(), folds.clone())), 2);
+
+        let folded = paper.folded_all(&folds).unwrap();
+        assert_eq!(folded.count(), 1);
+        assert!(folded.contains((0, 0)));
+        assert_eq!((folded.width(), folded.height()), (2000000000, 1000000000));
+
+        // no dense grid, and no picture of it
+        let too_large = TooLarge {
+            width: 2000000000,
+            height: 1000000000,
+        };
+        assert_eq!(folded.to_pbm(), Err(too_large));
+        assert_eq!(folded.to_text(), Err(too_large));
+        assert_eq!(
+            too_large.to_string(),
+            "sheet 2000000000x1000000000 too large to export"
+        );
+        let err = Day13::part_two(&(paper, folds)).unwrap_err();
+        assert_eq!(err.error, OcrError::Height(1000000000));
+        assert_eq!(err.picture, None);
+    }
+
+    #[test]
+    fn test_off_centre() {
+        let (paper, _) = Day13::parse("1,0\n1,4\n3,7\n\nfold along y=2\n").unwrap();
+        assert_eq!((paper.width(), paper.height()), (4, 8));
+
+        // the lower side is larger, so the upper side moves down
+        let up = paper.folded(&Fold::Y(2)).unwrap();
+        assert_eq!((up.width(), up.height()), (4, 5));
+        let mut dots: Vec<_> = up.dots.iter().copied().collect();
+        dots.sort_unstable();
+        assert_eq!(dots, vec![(1, 3), (3, 0)]);
+
+        // a fold outside the paper moves nothing
+        let left = paper.folded(&Fold::X(6)).unwrap();
+        assert_eq!((left.width(), left.height()), (6, 8));
+        assert_eq!(left.dots, paper.dots);
+
+        // the first column folds onto the last one
+        let right = paper.folded(&Fold::X(0)).unwrap();
+        assert_eq!(right.width(), 3);
+        assert!(right.contains((2, 0)) && right.contains((0, 7)));
+    }
+
+    #[test]
+    fn test_fold_through_dot() {
+        let (paper, _) = Day13::parse("0,0\n3,7\n\nfold along y=2\n").unwrap();
+        assert_eq!(
+            paper.folded(&Fold::X(3)),
+            Err(FoldError {
+                fold: Fold::X(3),
+                dot: (3, 7)
+            })
+        );
+
+        // after folding up, (3,7) is at (3,5), where the second fold is
+        let err = Day13::parse("0,0\n3,7\n\nfold along y=6\nfold along y=5\n").unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "<input>:5:1: expected a fold not through the dot at 3,5, found 'fold along y=5'"
+        );
+    }
+
+    /// Folds a dense grid, like the paper did before it was sparse
+    fn folded_dense(grid: &Grid<bool>, fold: &Fold) -> Grid<bool> {
+        let (line, size) = match *fold {
+            Fold::X(col) => (col, grid.width()),
+            Fold::Y(row) => (row, grid.height()),
+        };
+        let new_size = line.max(size.saturating_sub(line + 1));
+        let (width, height) = match *fold {
+            Fold::X(_) => (new_size, grid.height()),
+            Fold::Y(_) => (grid.width(), new_size),
+        };
+
+        let mut folded = Grid::new(width, height, false);
+        for (x, y) in folded.positions().collect::<Vec<_>>() {
+            let pos = match *fold {
+                Fold::X(_) => x,
+                Fold::Y(_) => y,
+            };
+            // the cells on both sides of the line that end up at pos
+            let sides = [
+                (pos + line).checked_sub(new_size),
+                Some(line + new_size - pos),
+            ];
+            folded[(x, y)] = sides.iter().flatten().any(|&other| {
+                let cell = match *fold {
+                    Fold::X(_) => grid.get((other, y)),
+                    Fold::Y(_) => grid.get((x, other)),
+                };
+                cell == Some(&true)
+            });
+        }
+        folded
+    }
+
+    #[test]
+    fn test_stages() {
+        let (paper, folds) = Day13::parse_file("inputs/day13_ocr_test").unwrap();
+        let stages = paper.stages(&folds).unwrap();
+        assert_eq!(stages.len(), 3);
+        assert_eq!(stages[2], paper.folded_all(&folds).unwrap());
+
+        // off-centre folds, and one outside of the paper
+        let input = "1,0\n1,4\n3,7\n6,2\n0,5\n\n\
+                     fold along x=2\nfold along y=9\nfold along x=1\nfold along y=3\n";
+        let (small, more) = Day13::parse(input).unwrap();
+        for (paper, folds) in [(&paper, &folds), (&small, &more)] {
+            let mut dense = paper.to_grid();
+            for (stage, fold) in paper.stages(folds).unwrap()[1..].iter().zip(folds) {
+                dense = folded_dense(&dense, fold);
+                assert_eq!(stage.to_grid(), dense, "differs after {}", fold);
+            }
+        }
+
+        assert!(stages[2]
+            .to_text()
+            .unwrap()
+            .starts_with("###...##..#..#.####.###..\n"));
+    }
+
+    #[test]
+    fn test_pbm() {
+        let (paper, _) = Day13::parse("0,0\n9,1\n\nfold along y=5\n").unwrap();
+        assert_eq!(paper.to_pbm(), Ok(b"P4\n10 2\n\x80\x00\x00\x40".to_vec()));
+    }
+
+    #[test]
+    fn test_part_two() {
+        let input = Day13::parse_file("inputs/day13_ocr_test").unwrap();
+        assert_eq!(Day13::part_one(&input), 69);
+        assert_eq!(Day13::part_two(&input), Ok(String::from("BAKER")));
+
+        // folds into a square, which is too high for letters
+        let input = Day13::parse_file("inputs/day13_test").unwrap();
+        let err = Day13::part_two(&input).unwrap_err();
+        assert_eq!(err.error, OcrError::Height(7));
+        assert_eq!(err.picture, None);
+        assert_eq!(
+            err.to_string(),
+            "letters are 6 dots high, the dots are 7 high"
+        );
+
+        // letter-sized, but no letter
+        let input = Day13::parse(
+            "0,0\n0,1\n0,2\n0,3\n0,4\n0,5\n2,0\n2,1\n2,2\n2,3\n2,4\n2,5\n\n\
+             fold along y=6\nfold along x=4\n",
+        )
+        .unwrap();
+        let err = Day13::part_two(&input).unwrap_err();
+        assert!(matches!(err.error, OcrError::UnknownGlyph { index: 0, .. }));
+        assert_eq!(err.picture, Some(String::from("# # \n").repeat(6)));
+        assert!(err
+            .to_string()
+            .ends_with("the folded dots:\n# # \n# # \n# # \n# # \n# # \n# # "));
     }
 }
@@ -1,64 +1,91 @@
 use std::collections::BTreeMap;
-use std::{fs, io};
-
-fn parse(filename: &str) -> io::Result<(String, BTreeMap<(char, char), char>)> {
-    let file = fs::read_to_string(filename)?;
-    let mut parts = file.split("\n\n");
-
-    let start = parts.next().unwrap().to_string();
-    let replacements = parts
-        .next()
-        .unwrap()
-        .lines()
-        .map(|x| {
-            let mut p = x.split(" -> ");
-            let mut pat = p.next().unwrap().chars();
-            (
-                (pat.next().unwrap(), pat.next().unwrap()),
-                p.next().unwrap().chars().next().unwrap(),
-            )
-        })
-        .collect();
-
-    Ok((start, replacements))
-}
 
-fn part(start: &String, instructions: &BTreeMap<(char, char), char>, n: usize) -> usize {
-    let mut state = BTreeMap::new();
-    let mut counts = BTreeMap::new();
+use crate::bigint::BigUint;
+use crate::error::ParseError;
+use crate::Solution;
+
+pub type Rules = BTreeMap<(char, char), char>;
+
+/// Difference between the most and least common element after `n` steps
+///
+/// The counts double about every step, so they are exact big integers.
+pub fn part(start: &str, instructions: &Rules, n: usize) -> BigUint {
+    let mut state: BTreeMap<(char, char), BigUint> = BTreeMap::new();
+    let mut counts: BTreeMap<char, BigUint> = BTreeMap::new();
+    let one = BigUint::from(1usize);
 
     let chars: Vec<char> = start.chars().collect();
     for c in chars.windows(2) {
-        *state.entry((c[0], c[1])).or_insert(0) += 1;
+        *state.entry((c[0], c[1])).or_default() += &one;
     }
 
     for c in chars {
-        *counts.entry(c).or_insert(0) += 1;
+        *counts.entry(c).or_default() += &one;
     }
 
     for _ in 0..n {
         let save_state = state.clone();
         for (pair, count) in save_state {
-            match instructions.get(&pair) {
-                Some(c) => {
-                    *state.entry((pair.0, pair.1)).or_insert(0) -= count;
-                    *state.entry((pair.0, *c)).or_insert(0) += count;
-                    *state.entry((*c, pair.1)).or_insert(0) += count;
-                    *counts.entry(*c).or_insert(0) += count;
-                }
-                None => (),
+            if let Some(c) = instructions.get(&pair) {
+                *state.entry((pair.0, pair.1)).or_default() -= &count;
+                *state.entry((pair.0, *c)).or_default() += &count;
+                *state.entry((*c, pair.1)).or_default() += &count;
+                *counts.entry(*c).or_default() += &count;
             }
         }
     }
 
-    counts.values().max().unwrap() - counts.values().min().unwrap()
+    counts.values().max().unwrap().clone() - counts.values().min().unwrap()
 }
 
-fn main() -> Result<(), Box<dyn std::error::Error + 'static>> {
-    let (start, instructions) = parse("inputs/day14")?;
-    println!("Answer Part One: {}", part(&start, &instructions, 10));
-    println!("Answer Part Two: {}", part(&start, &instructions, 40));
-    Ok(())
+pub struct Day14;
+
+impl Solution for Day14 {
+    const DAY: u8 = 14;
+
+    /// Polymer template and pair insertion rules
+    type Input = (String, Rules);
+    type AnswerOne = BigUint;
+    type AnswerTwo = BigUint;
+
+    fn parse(input: &str) -> Result<(String, Rules), ParseError> {
+        let (start, rules) = input
+            .split_once("\n\n")
+            .ok_or_else(|| ParseError::missing(input, input, "an empty line and rules"))?;
+
+        if start.is_empty() {
+            return Err(ParseError::missing(input, start, "a polymer template"));
+        }
+
+        let replacements = rules
+            .lines()
+            .map(|x| {
+                let (pat, insert) = x
+                    .split_once(" -> ")
+                    .ok_or_else(|| ParseError::missing(input, x, "' -> ' and an element"))?;
+
+                let pat: Vec<char> = pat.chars().collect();
+                if pat.len() != 2 {
+                    return Err(ParseError::invalid(input, x, "a pair of elements"));
+                }
+                let mut insert_chars = insert.chars();
+                match (insert_chars.next(), insert_chars.next()) {
+                    (Some(c), None) => Ok(((pat[0], pat[1]), c)),
+                    _ => Err(ParseError::invalid(input, insert, "a single element")),
+                }
+            })
+            .collect::<Result<_, _>>()?;
+
+        Ok((start.to_string(), replacements))
+    }
+
+    fn part_one((start, instructions): &(String, Rules)) -> BigUint {
+        part(start, instructions, 10)
+    }
+
+    fn part_two((start, instructions): &(String, Rules)) -> BigUint {
+        part(start, instructions, 40)
+    }
 }
 
 #[cfg(test)]
@@ -67,7 +94,7 @@ mod tests {
 
     #[test]
     fn test_parse() {
-        let (start, instructions) = parse("inputs/day14_test").unwrap();
+        let (start, instructions) = Day14::parse_file("inputs/day14_test").unwrap();
         assert!(start == "NNCB");
         assert!(instructions[&('C', 'H')] == 'B');
         assert!(instructions[&('C', 'N')] == 'C');
@@ -75,13 +102,23 @@ mod tests {
 
     #[test]
     fn test_part_one() {
-        let (start, instructions) = parse("inputs/day14_test").unwrap();
-        assert_eq!(part(&start, &instructions, 10), 1588);
+        let (start, instructions) = Day14::parse_file("inputs/day14_test").unwrap();
+        assert_eq!(part(&start, &instructions, 10).to_string(), "1588");
     }
 
     #[test]
     fn test_part_two() {
-        let (start, instructions) = parse("inputs/day14_test").unwrap();
-        assert_eq!(part(&start, &instructions, 40), 2188189693529);
+        let (start, instructions) = Day14::parse_file("inputs/day14_test").unwrap();
+        assert_eq!(part(&start, &instructions, 40).to_string(), "2188189693529");
+    }
+
+    #[test]
+    fn test_long_run() {
+        let (start, instructions) = Day14::parse_file("inputs/day14_test").unwrap();
+        // would overflow usize after about 60 steps
+        assert_eq!(
+            part(&start, &instructions, 100).to_string(),
+            "2535296262066596202993060773164"
+        );
     }
 }
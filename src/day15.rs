@@ -1,126 +1,387 @@
-use std::collections::HashSet;
-use std::fmt::Display;
-use std::{fs, io};
+use std::cmp::{Ordering, Reverse};
+use std::collections::BinaryHeap;
+use std::fmt::{self, Display};
+use std::time::{Duration, Instant};
 
+use crate::error::ParseError;
+use crate::grid::Grid;
+use crate::Solution;
+
+/// Number of times the cave is repeated in each direction in part two
+const TILE_FACTOR: usize = 5;
+
+/// A rectangular map of risk levels
+pub trait RiskMap {
+    fn width(&self) -> usize;
+    fn height(&self) -> usize;
+    fn risk(&self, pos: (usize, usize)) -> u8;
+}
+
+impl RiskMap for Grid<u8> {
+    fn width(&self) -> usize {
+        Grid::width(self)
+    }
+
+    fn height(&self) -> usize {
+        Grid::height(self)
+    }
+
+    fn risk(&self, pos: (usize, usize)) -> u8 {
+        self[pos]
+    }
+}
+
+/// The cave repeated `factor` times to the right and down
+///
+/// Each tile's risk is one higher than the tile to its left or above,
+/// wrapping from 9 back to 1. Risks are computed on access, so the
+/// tiles are never stored. A search over them keeps a `u32` cost and a
+/// one byte link per cell.
+pub struct Tiled<'a> {
+    field: &'a Grid<u8>,
+    factor: usize,
+}
+
+impl<'a> Tiled<'a> {
+    pub fn new(field: &'a Grid<u8>, factor: usize) -> Tiled<'a> {
+        Tiled { field, factor }
+    }
+}
+
+impl RiskMap for Tiled<'_> {
+    fn width(&self) -> usize {
+        self.field.width() * self.factor
+    }
+
+    fn height(&self) -> usize {
+        self.field.height() * self.factor
+    }
+
+    fn risk(&self, (x, y): (usize, usize)) -> u8 {
+        let (w, h) = (self.field.width(), self.field.height());
+        let increase = x / w + y / h;
+        ((self.field[(x % w, y % h)] as usize - 1 + increase) % 9 + 1) as u8
+    }
+}
+
+/// A path through the cave and its total risk
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Path {
+    /// Sum of the risks of all cells except the first
+    pub risk: usize,
+    /// Visited cells, from the top left to the bottom right corner
+    pub cells: Vec<(usize, usize)>,
+}
+
+/// Ways to search for the lowest risk path
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Algorithm {
+    Dijkstra,
+    /// Dijkstra guided by the Manhattan distance to the target
+    AStar,
+    /// Dijkstra from both corners at once, until the searches meet
+    Bidirectional,
+}
+
+impl Algorithm {
+    pub const ALL: [Algorithm; 3] = [
+        Algorithm::Dijkstra,
+        Algorithm::AStar,
+        Algorithm::Bidirectional,
+    ];
+}
+
+impl Display for Algorithm {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Algorithm::Dijkstra => "Dijkstra",
+            Algorithm::AStar => "A*",
+            Algorithm::Bidirectional => "bidirectional",
+        };
+        f.pad(name)
+    }
+}
+
+/// The result of a search, with the number of cells it expanded
 #[derive(Debug, Clone)]
-struct Field<T> {
-    numbers: Vec<T>,
-    pub rows: usize,
-    pub cols: usize,
-}
-
-impl Field<u8> {
-    fn from_str(s: &str) -> Field<u8> {
-        let numbers = s
-            .replace("\n", "")
-            .chars()
-            .map(|c| c.to_digit(10).unwrap() as u8)
-            .collect();
-        let rows = s.lines().count();
-        let cols = s.lines().next().unwrap().len();
-
-        Field {
-            numbers,
-            rows,
-            cols,
-        }
+pub struct Search {
+    pub path: Path,
+    pub expanded: usize,
+}
+
+/// The result of one algorithm in [`compare`]
+#[derive(Debug, Clone)]
+pub struct Comparison {
+    pub algorithm: Algorithm,
+    pub risk: usize,
+    pub expanded: usize,
+    pub time: Duration,
+}
+
+/// Lowest risk path from the top left to the bottom right corner
+pub fn search(map: &impl RiskMap, algorithm: Algorithm) -> Search {
+    let (tx, ty) = (map.width() - 1, map.height() - 1);
+    match algorithm {
+        Algorithm::Dijkstra => best_first(map, |_| 0),
+        // every step costs at least 1, so this never overestimates
+        Algorithm::AStar => best_first(map, |(x, y)| tx - x + ty - y),
+        Algorithm::Bidirectional => bidirectional(map),
     }
+}
 
-    fn get_mut(&mut self, x: isize, y: isize) -> Option<&mut u8> {
-        if x < self.cols as isize && 0 <= x && y < self.rows as isize && 0 <= y {
-            Some(&mut self.numbers[self.cols * y as usize + x as usize])
-        } else {
-            None
+/// Lowest risk path found with Dijkstra
+pub fn dijkstra(map: &impl RiskMap) -> Path {
+    search(map, Algorithm::Dijkstra).path
+}
+
+/// Runs every [`Algorithm`] on `map`
+pub fn compare(map: &impl RiskMap) -> Vec<Comparison> {
+    Algorithm::ALL
+        .iter()
+        .map(|&algorithm| {
+            let start = Instant::now();
+            let result = search(map, algorithm);
+            Comparison {
+                algorithm,
+                risk: result.path.risk,
+                expanded: result.expanded,
+                time: start.elapsed(),
+            }
+        })
+        .collect()
+}
+
+/// Where the neighbour a cell links to lies, so links take a byte per cell
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Direction {
+    Left,
+    Right,
+    Up,
+    Down,
+}
+
+impl Direction {
+    /// Direction of the neighbour `to` as seen from `from`
+    fn towards(from: (usize, usize), to: (usize, usize)) -> Direction {
+        match (to.0.cmp(&from.0), to.1.cmp(&from.1)) {
+            (Ordering::Less, _) => Direction::Left,
+            (Ordering::Greater, _) => Direction::Right,
+            (_, Ordering::Less) => Direction::Up,
+            _ => Direction::Down,
         }
     }
 
-    fn get(&self, x: isize, y: isize) -> Option<&u8> {
-        if x < self.cols as isize && 0 <= x && y < self.rows as isize && 0 <= y {
-            Some(&self.numbers[self.cols * y as usize + x as usize])
-        } else {
-            None
+    fn step(self, (x, y): (usize, usize)) -> (usize, usize) {
+        match self {
+            Direction::Left => (x - 1, y),
+            Direction::Right => (x + 1, y),
+            Direction::Up => (x, y - 1),
+            Direction::Down => (x, y + 1),
         }
     }
+}
 
-    fn get_coord(&self, index: usize) -> (isize, isize) {
-        (
-            index as isize % self.cols as isize,
-            index as isize / self.cols as isize,
-        )
+/// Follows `links` from `pos` until a cell without one
+fn follow(links: &Grid<Option<Direction>>, pos: (usize, usize)) -> Vec<(usize, usize)> {
+    let mut cells = vec![pos];
+    let mut pos = pos;
+    while let Some(direction) = links[pos] {
+        pos = direction.step(pos);
+        cells.push(pos);
     }
+    cells
+}
 
-    fn dijkstra_cost(&self) -> usize {
-        let mut cost = Field {
-            numbers: vec![usize::MAX; self.cols * self.rows-1],
-            rows: self.rows,
-            cols: self.cols,
-        };
-        cost.numbers.insert(0, 0);
+/// A grid of costs for `map`, all unreached
+///
+/// Costs are `u32` to keep large maps small. A cell is never reached
+/// more expensively than one step after the path straight down and right
+/// to its neighbour, so twice that (when searching from both ends) fits.
+fn unreached(map: &impl RiskMap) -> Grid<u32> {
+    let max_risk = (map.width() + map.height()).saturating_mul(9);
+    assert!(max_risk < (u32::MAX / 2) as usize, "the cave is too large");
+    Grid::new(map.width(), map.height(), u32::MAX)
+}
 
-        let mut unvisited = HashSet::with_capacity(self.rows * self.cols);
-        for x in 0..self.cols {
-            for y in 0..self.rows {
-                unvisited.insert((x, y));
-            }
+/// Dijkstra, or A* with a non-zero `heuristic`
+fn best_first(map: &impl RiskMap, heuristic: impl Fn((usize, usize)) -> usize) -> Search {
+    let target = (map.width() - 1, map.height() - 1);
+    let mut cost = unreached(map);
+    let mut previous = Grid::new(map.width(), map.height(), None);
+    let mut expanded = 0;
+    cost[(0, 0)] = 0;
+
+    // min-heap of (estimated total cost, cost so far, position)
+    let mut queue = BinaryHeap::new();
+    queue.push(Reverse((heuristic((0, 0)), 0, (0, 0))));
+
+    while let Some(Reverse((_, risk, pos))) = queue.pop() {
+        if pos == target {
+            let mut cells = follow(&previous, pos);
+            cells.reverse();
+            let path = Path {
+                risk: risk as usize,
+                cells,
+            };
+            return Search { path, expanded };
+        }
+        // stale entry, pos was already reached cheaper
+        if risk > cost[pos] {
+            continue;
         }
+        expanded += 1;
 
-        unvisited.iter
-
-        loop {
-            let current = paths.pop().unwrap();
-            let (x, y) = current.1.last().unwrap();
-
-            for (dx, dy) in [(0, 1), (1, 0), (0, -1), (-1, 0)] {
-                match self.get(x + dx, y + dy) {
-                    Some(cost) => match current.1.iter().find(|(ox, oy)| ox == x && oy == oy) {
-                        None => {
-                            let mut newpath = current.1.clone();
-                            newpath.push((x + dx, y + dy));
-                            paths.push((current.0 + cost, newpath))
-                        }
-                        Some(_) => (),
-                    },
-                    None => (),
-                }
+        for next in cost.neighbours4(pos) {
+            let next_risk = risk + map.risk(next) as u32;
+            if next_risk < cost[next] {
+                cost[next] = next_risk;
+                previous[next] = Some(Direction::towards(next, pos));
+                let estimate = next_risk as usize + heuristic(next);
+                queue.push(Reverse((estimate, next_risk, next)));
             }
         }
-        0
     }
+    unreachable!("the bottom right corner is always reachable")
 }
 
-impl Display for Field<u8> {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        let s: String = self
-            .numbers
-            .chunks(self.cols)
-            .map(|x| x.iter().map(|x| format!("{}", x)).collect::<String>() + "\n")
-            .collect();
+/// Dijkstra from the start and the target, expanding the cheaper side first
+///
+/// The backward search costs a cell with the risks of the cells after it,
+/// so the forward and backward cost of a cell add up to the path's risk.
+fn bidirectional(map: &impl RiskMap) -> Search {
+    let (start, target) = ((0, 0), (map.width() - 1, map.height() - 1));
+    let mut forward = unreached(map);
+    let mut backward = forward.clone();
+    let mut previous = Grid::new(map.width(), map.height(), None);
+    let mut next = previous.clone();
+    forward[start] = 0;
+    backward[target] = 0;
 
-        write!(f, "{}", s)
+    let mut forward_queue = BinaryHeap::from([Reverse((0, start))]);
+    let mut backward_queue = BinaryHeap::from([Reverse((0, target))]);
+    let mut expanded = 0;
+    // cheapest path seen so far, as (risk, cell where both searches met)
+    let mut best = (u32::MAX, start);
+    if start == target {
+        best = (0, start);
     }
-}
 
-fn parse(filename: &str) -> io::Result<Field<u8>> {
-    let file = fs::read_to_string(filename)?;
+    while let (Some(Reverse((f, _))), Some(Reverse((b, _)))) =
+        (forward_queue.peek(), backward_queue.peek())
+    {
+        // every path not seen yet is at least this expensive
+        if f + b >= best.0 {
+            break;
+        }
 
-    Ok(Field::from_str(&file))
+        if f <= b {
+            let Reverse((risk, pos)) = forward_queue.pop().unwrap();
+            if risk > forward[pos] {
+                continue;
+            }
+            expanded += 1;
+            for n in forward.neighbours4(pos) {
+                let n_risk = risk + map.risk(n) as u32;
+                if n_risk < forward[n] {
+                    forward[n] = n_risk;
+                    previous[n] = Some(Direction::towards(n, pos));
+                    forward_queue.push(Reverse((n_risk, n)));
+                    if backward[n] != u32::MAX && n_risk + backward[n] < best.0 {
+                        best = (n_risk + backward[n], n);
+                    }
+                }
+            }
+        } else {
+            let Reverse((risk, pos)) = backward_queue.pop().unwrap();
+            if risk > backward[pos] {
+                continue;
+            }
+            expanded += 1;
+            // stepping from n onto pos costs the risk of pos
+            let n_risk = risk + map.risk(pos) as u32;
+            for n in backward.neighbours4(pos) {
+                if n_risk < backward[n] {
+                    backward[n] = n_risk;
+                    next[n] = Some(Direction::towards(n, pos));
+                    backward_queue.push(Reverse((n_risk, n)));
+                    if forward[n] != u32::MAX && forward[n] + n_risk < best.0 {
+                        best = (forward[n] + n_risk, n);
+                    }
+                }
+            }
+        }
+    }
+
+    let (risk, meeting) = best;
+    let mut cells = follow(&previous, meeting);
+    cells.reverse();
+    cells.extend(&follow(&next, meeting)[1..]);
+    Search {
+        path: Path {
+            risk: risk as usize,
+            cells,
+        },
+        expanded,
+    }
 }
 
-fn part_one(field: &Field<u8>) -> usize {
-    0
+/// A risk in an [`overlay`], bracketed if it is on the path
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Marked {
+    pub risk: u8,
+    pub on_path: bool,
 }
 
-fn part_two(field: &Field<u8>) -> usize {
-    0
+impl Display for Marked {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.on_path {
+            true => write!(f, "[{}]", self.risk),
+            false => write!(f, " {} ", self.risk),
+        }
+    }
+}
+
+/// The risk map with the cells of `path` marked
+///
+/// Print it to see the path, its `Display` is the one of the risk map.
+pub fn overlay(map: &impl RiskMap, path: &Path) -> Grid<Marked> {
+    let cells = (0..map.height())
+        .flat_map(|y| (0..map.width()).map(move |x| (x, y)))
+        .map(|pos| Marked {
+            risk: map.risk(pos),
+            on_path: false,
+        })
+        .collect();
+    let mut rendered = Grid::from_vec(map.width(), map.height(), cells);
+    for &pos in &path.cells {
+        rendered[pos].on_path = true;
+    }
+    rendered
 }
 
-fn main() -> Result<(), Box<dyn std::error::Error + 'static>> {
-    let field = parse("inputs/day11").unwrap();
+pub struct Day15;
+
+impl Solution for Day15 {
+    const DAY: u8 = 15;
+
+    /// Risk level map of the cave
+    type Input = Grid<u8>;
+    type AnswerOne = usize;
+    type AnswerTwo = usize;
+
+    fn parse(input: &str) -> Result<Grid<u8>, ParseError> {
+        // a risk of 0 would wrap to 9 in the tiles instead of to 1
+        let risk = |c: char| c.to_digit(10).filter(|&d| d > 0).map(|d| d as u8);
+        Grid::from_chars(input, risk, "a risk from 1 to 9")
+    }
 
-    println!("Answer part one: {}", part_one(&field));
-    println!("Answer part two: {}", part_two(&field));
+    fn part_one(field: &Grid<u8>) -> usize {
+        dijkstra(field).risk
+    }
 
-    Ok(())
+    fn part_two(field: &Grid<u8>) -> usize {
+        dijkstra(&Tiled::new(field, TILE_FACTOR)).risk
+    }
 }
 
 #[cfg(test)]
@@ -129,16 +390,120 @@ mod tests {
 
     #[test]
     fn test_input() {
-        let field = parse("inputs/day15_test").unwrap();
+        let field = Day15::parse_file("inputs/day15_test").unwrap();
         println!("{}", field);
-        assert_eq!(field.cols, 10);
-        assert_eq!(field.rows, 10);
-        assert_eq!(field.numbers[2], 6);
+        assert_eq!(field.width(), 10);
+        assert_eq!(field.height(), 10);
+        assert_eq!(field[(2, 0)], 6);
+    }
+
+    #[test]
+    fn test_parse_error() {
+        let err = Day15::parse("1103\n1381\n").unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "<input>:1:3: expected a risk from 1 to 9, found '0'"
+        );
+    }
+
+    #[test]
+    fn test_part_one() {
+        let field = Day15::parse_file("inputs/day15_test").unwrap();
+        assert_eq!(Day15::part_one(&field), 40);
+    }
+
+    #[test]
+    fn test_path() {
+        let field = Day15::parse_file("inputs/day15_test").unwrap();
+        let path = dijkstra(&field);
+        assert_eq!(path.cells.first(), Some(&(0, 0)));
+        assert_eq!(path.cells.last(), Some(&(9, 9)));
+        assert_eq!(
+            path.cells[1..]
+                .iter()
+                .map(|&p| field[p] as usize)
+                .sum::<usize>(),
+            path.risk
+        );
+        for step in path.cells.windows(2) {
+            assert!(field.neighbours4(step[0]).any(|n| n == step[1]));
+        }
+
+        let rendered = overlay(&field, &path).to_string();
+        println!("{}", rendered);
+        assert!(rendered.starts_with("[1] 1  6  3  7  5  1  7  4  2 \n[1] 3 "));
+        assert!(rendered.ends_with(" 8 [1]\n"));
+        // every risk is still there, the path's in brackets
+        let digits: String = rendered.chars().filter(char::is_ascii_digit).collect();
+        assert_eq!(digits, field.to_string().replace('\n', ""));
+        assert_eq!(rendered.matches('[').count(), path.cells.len());
+    }
+
+    #[test]
+    fn test_algorithms_agree() {
+        let field = Day15::parse_file("inputs/day15_test").unwrap();
+        let tiled = Tiled::new(&field, TILE_FACTOR);
+        for (risk, results) in [(40, compare(&field)), (315, compare(&tiled))] {
+            for result in results {
+                assert_eq!(result.risk, risk, "{} disagrees", result.algorithm);
+            }
+        }
+
+        for algorithm in Algorithm::ALL {
+            let path = search(&tiled, algorithm).path;
+            assert_eq!(path.cells.first(), Some(&(0, 0)));
+            assert_eq!(path.cells.last(), Some(&(49, 49)));
+            let risk: usize = path.cells[1..]
+                .iter()
+                .map(|&p| tiled.risk(p) as usize)
+                .sum();
+            assert_eq!(risk, path.risk, "{} returned a wrong path", algorithm);
+            for step in path.cells.windows(2) {
+                let (dx, dy) = (step[0].0.abs_diff(step[1].0), step[0].1.abs_diff(step[1].1));
+                assert_eq!(dx + dy, 1, "{} jumped from {:?}", algorithm, step[0]);
+            }
+        }
+
+        let single = Grid::new(1, 1, 5);
+        for algorithm in Algorithm::ALL {
+            assert_eq!(search(&single, algorithm).path.cells, vec![(0, 0)]);
+        }
+    }
+
+    #[test]
+    fn test_direction() {
+        // a link per cell fits in one byte
+        assert_eq!(std::mem::size_of::<Option<Direction>>(), 1);
+        for next in [(0, 1), (2, 1), (1, 0), (1, 2)] {
+            assert_eq!(Direction::towards((1, 1), next).step((1, 1)), next);
+        }
+    }
+
+    #[test]
+    fn test_tiled() {
+        let field = Day15::parse_file("inputs/day15_test").unwrap();
+        let tiled = Tiled::new(&field, TILE_FACTOR);
+        assert_eq!(tiled.width(), 50);
+        assert_eq!(tiled.height(), 50);
+        assert_eq!(tiled.risk((0, 0)), 1);
+        assert_eq!(tiled.risk((10, 0)), 2);
+        assert_eq!(tiled.risk((49, 49)), 9);
+        // 7 in the original, +4 wraps around to 2
+        assert_eq!(tiled.risk((44, 0)), 2);
+
+        let same = Tiled::new(&field, 1);
+        assert_eq!(dijkstra(&same), dijkstra(&field));
+    }
+
+    #[test]
+    fn test_part_two() {
+        let field = Day15::parse_file("inputs/day15_test").unwrap();
+        assert_eq!(Day15::part_two(&field), 315);
     }
 
     #[test]
     fn test_pos() {
-        let field = parse("inputs/day11_test").unwrap();
+        let field = Day15::parse_file("inputs/day11_test").unwrap();
         println!("{}", field);
         assert_eq!(field.get_coord(10), (0, 1));
         assert_eq!(field.get_coord(0), (0, 0));
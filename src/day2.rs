@@ -1,32 +1,32 @@
-use std::fs;
-use std::io;
+use crate::error::{parse_token, ParseError};
+use crate::Solution;
 
-enum Instruction {
+#[derive(Debug)]
+pub enum Instruction {
     Up(i32),
     Down(i32),
     Forward(i32),
 }
 
 impl Instruction {
-    fn from_string(string: &str) -> Instruction {
-        let mut parts = string.split(' ');
-        let inst = parts.next().expect("Line not long enough");
-        let number = parts
-            .next()
-            .expect("Line not long enough")
-            .parse::<i32>()
-            .expect("Second part of line has to be a number");
+    /// Parses `line` of `input`, e.g. 'forward 5'
+    fn from_string(input: &str, line: &str) -> Result<Instruction, ParseError> {
+        let (inst, number) = line
+            .split_once(' ')
+            .ok_or_else(|| ParseError::missing(input, line, "a space and a number"))?;
+        let number = parse_token(input, number, "a number")?;
 
         match inst {
-            "up" => Instruction::Up(number),
-            "down" => Instruction::Down(number),
-            "forward" => Instruction::Forward(number),
-            _ => panic!("Unknown instruction {}", inst),
+            "up" => Ok(Instruction::Up(number)),
+            "down" => Ok(Instruction::Down(number)),
+            "forward" => Ok(Instruction::Forward(number)),
+            _ => Err(ParseError::invalid(input, inst, "up, down or forward")),
         }
     }
 }
 
-fn part_one(instructions: &Vec<Instruction>) -> (i32, i32) {
+/// Final (depth, position) when up and down move the submarine directly
+fn travel(instructions: &[Instruction]) -> (i32, i32) {
     let mut depth = 0;
     let mut position = 0;
     for i in instructions {
@@ -40,7 +40,8 @@ fn part_one(instructions: &Vec<Instruction>) -> (i32, i32) {
     (depth, position)
 }
 
-fn part_two(instructions: &Vec<Instruction>) -> (i32, i32) {
+/// Final (depth, position) when up and down only change the aim
+fn travel_with_aim(instructions: &[Instruction]) -> (i32, i32) {
     let mut depth: i32 = 0;
     let mut position: i32 = 0;
     let mut aim: i32 = 0;
@@ -59,25 +60,31 @@ fn part_two(instructions: &Vec<Instruction>) -> (i32, i32) {
     (depth, position)
 }
 
-fn parse(filename: &str) -> io::Result<Vec<Instruction>> {
-    Ok(fs::read_to_string(filename)?
-        .lines()
-        .map(|x| Instruction::from_string(x))
-        .collect())
-}
+pub struct Day2;
+
+impl Solution for Day2 {
+    const DAY: u8 = 2;
 
-fn main() -> Result<(), Box<dyn std::error::Error + 'static>> {
-    let instructions = parse("inputs/day2")?;
+    type Input = Vec<Instruction>;
+    type AnswerOne = i32;
+    type AnswerTwo = i32;
 
-    let (depth, position) = part_one(&instructions);
-    println!("Depth, position part1: {}, {}", depth, position);
-    println!("Multiplied: {}\n", depth * position);
+    fn parse(input: &str) -> Result<Vec<Instruction>, ParseError> {
+        input
+            .lines()
+            .map(|line| Instruction::from_string(input, line))
+            .collect()
+    }
 
-    let (depth, position) = part_two(&instructions);
-    println!("Depth, position part2: {}, {}", depth, position);
-    println!("Multiplied: {}", depth * position);
+    fn part_one(instructions: &Vec<Instruction>) -> i32 {
+        let (depth, position) = travel(instructions);
+        depth * position
+    }
 
-    Ok(())
+    fn part_two(instructions: &Vec<Instruction>) -> i32 {
+        let (depth, position) = travel_with_aim(instructions);
+        depth * position
+    }
 }
 
 #[cfg(test)]
@@ -86,9 +93,23 @@ mod tests {
 
     #[test]
     fn test_data() {
-        let instructions = parse("inputs/day2_test").unwrap();
+        let instructions = Day2::parse_file("inputs/day2_test").unwrap();
+
+        assert_eq!(travel(&instructions), (10, 15));
+        assert_eq!(travel_with_aim(&instructions), (60, 15));
+        assert_eq!(Day2::part_one(&instructions), 150);
+        assert_eq!(Day2::part_two(&instructions), 900);
+    }
 
-        assert_eq!(part_one(&instructions), (10, 15));
-        assert_eq!(part_two(&instructions), (60, 15));
+    #[test]
+    fn test_parse_error() {
+        let err = Day2::parse("forward 5\nbackward 3\n").unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "<input>:2:1: expected up, down or forward, found 'backward'"
+        );
+
+        let err = Day2::parse("up x").unwrap_err();
+        assert_eq!(err.location().column, 4);
     }
 }
@@ -1,7 +1,8 @@
-use std::fs;
-use std::io;
+use crate::error::ParseError;
+use crate::Solution;
 
-fn part_one(size: usize, bytes: &Vec<u32>) -> (u32, u32) {
+/// The (gamma, epsilon) rates of the diagnostic report
+fn rates(size: usize, bytes: &[u32]) -> (u32, u32) {
     let result = (0..size)
         .map(|i| u32::pow(2, i as u32))
         .map(|mask| {
@@ -12,18 +13,16 @@ fn part_one(size: usize, bytes: &Vec<u32>) -> (u32, u32) {
     (result, u32::pow(2, size as u32) - 1 - result)
 }
 
-fn part_two(size: usize, bytes: &Vec<u32>) -> (u32, u32) {
+/// The (oxygen, carbon) ratings of the diagnostic report
+fn ratings(size: usize, bytes: &[u32]) -> (u32, u32) {
     let mut oxygen = 0;
-    let mut ox_bytes = bytes.clone();
+    let mut ox_bytes = bytes.to_vec();
     for i in (0..size).rev() {
         let mask = u32::pow(2, i as u32);
         let len = ox_bytes.iter().filter(|b| (*b & mask) > 0).count();
         let most_common_bit = len * 2 >= ox_bytes.len();
 
-        ox_bytes = ox_bytes
-            .into_iter()
-            .filter(|b| ((*b & mask) != 0) == most_common_bit)
-            .collect();
+        ox_bytes.retain(|b| ((*b & mask) != 0) == most_common_bit);
 
         if ox_bytes.len() == 1 {
             oxygen = ox_bytes[0];
@@ -32,16 +31,13 @@ fn part_two(size: usize, bytes: &Vec<u32>) -> (u32, u32) {
     }
 
     let mut carbon = 0;
-    let mut c_bytes = bytes.clone();
+    let mut c_bytes = bytes.to_vec();
     for i in (0..size).rev() {
         let mask = u32::pow(2, i as u32);
         let len = c_bytes.iter().filter(|b| (*b & mask) > 0).count();
         let most_common_bit = len * 2 < c_bytes.len();
 
-        c_bytes = c_bytes
-            .into_iter()
-            .filter(|b| ((*b & mask) != 0) == most_common_bit)
-            .collect();
+        c_bytes.retain(|b| ((*b & mask) != 0) == most_common_bit);
 
         if c_bytes.len() == 1 {
             carbon = c_bytes[0];
@@ -52,39 +48,55 @@ fn part_two(size: usize, bytes: &Vec<u32>) -> (u32, u32) {
     (oxygen, carbon)
 }
 
-fn parse(filename: &str) -> io::Result<(usize, Vec<u32>)> {
-    let file = fs::read_to_string(filename)?;
-    let lines: Vec<_> = file.lines().collect();
-    let size = lines.iter().next().unwrap().len();
-
-    Ok((
-        size,
-        lines
-            .iter()
-            .map(|x| u32::from_str_radix(x, 2).unwrap())
-            .collect(),
-    ))
-}
+/// Widest numbers, so that `2^size` still fits a `u32`
+const MAX_SIZE: usize = 31;
+
+pub struct Day3;
+
+impl Solution for Day3 {
+    const DAY: u8 = 3;
+
+    /// Bit width and the numbers of the report
+    type Input = (usize, Vec<u32>);
+    /// Products of two numbers of up to 31 bits
+    type AnswerOne = u64;
+    type AnswerTwo = u64;
+
+    fn parse(input: &str) -> Result<(usize, Vec<u32>), ParseError> {
+        let size = match input.lines().next() {
+            Some(line) if line.len() > MAX_SIZE => {
+                let expected = format!("a binary number of at most {} digits", MAX_SIZE);
+                return Err(ParseError::invalid(input, line, expected));
+            }
+            Some(line) => line.len(),
+            None => return Err(ParseError::missing(input, input, "a binary number")),
+        };
+
+        let bytes = input
+            .lines()
+            .map(|x| match x.len() == size {
+                true => u32::from_str_radix(x, 2)
+                    .map_err(|_| ParseError::invalid(input, x, "a binary number")),
+                false => Err(ParseError::invalid(
+                    input,
+                    x,
+                    format!("a binary number with {} digits", size),
+                )),
+            })
+            .collect::<Result<_, _>>()?;
+
+        Ok((size, bytes))
+    }
+
+    fn part_one((size, bytes): &(usize, Vec<u32>)) -> u64 {
+        let (gamma, epsilon) = rates(*size, bytes);
+        gamma as u64 * epsilon as u64
+    }
 
-fn main() -> Result<(), Box<dyn std::error::Error + 'static>> {
-    let (size, bytes) = parse("inputs/day3").unwrap();
-    let (gamma, epsilon) = part_one(size, &bytes);
-    println!(
-        "Part 1: γ={} ε={}\nMultiplied: {}\n",
-        gamma,
-        epsilon,
-        gamma * epsilon
-    );
-
-    let (oxygen, carbon) = part_two(size, &bytes);
-    println!(
-        "Part 2: O={} C={}\nMultiplied: {}e",
-        oxygen,
-        carbon,
-        oxygen * carbon
-    );
-
-    Ok(())
+    fn part_two((size, bytes): &(usize, Vec<u32>)) -> u64 {
+        let (oxygen, carbon) = ratings(*size, bytes);
+        oxygen as u64 * carbon as u64
+    }
 }
 
 #[cfg(test)]
@@ -93,20 +105,44 @@ mod tests {
 
     #[test]
     fn test_input() {
-        let bytes = parse("inputs/day3_test").unwrap();
+        let bytes = Day3::parse_file("inputs/day3_test").unwrap();
         assert_eq!(bytes.1[0], 4);
         assert_eq!(bytes.1[1], 30);
     }
 
     #[test]
     fn test_part1() {
-        let (size, bytes) = parse("inputs/day3_test").unwrap();
-        assert_eq!(part_one(size, &bytes), (22, 9));
+        let (size, bytes) = Day3::parse_file("inputs/day3_test").unwrap();
+        assert_eq!(rates(size, &bytes), (22, 9));
+        assert_eq!(Day3::part_one(&(size, bytes)), 198);
     }
 
     #[test]
     fn test_part2() {
-        let (size, bytes) = parse("inputs/day3_test").unwrap();
-        assert_eq!(part_two(size, &bytes), (23, 10));
+        let (size, bytes) = Day3::parse_file("inputs/day3_test").unwrap();
+        assert_eq!(ratings(size, &bytes), (23, 10));
+        assert_eq!(Day3::part_two(&(size, bytes)), 230);
+    }
+
+    #[test]
+    fn test_wide() {
+        let widest = format!("{}\n{}\n", "1".repeat(31), "0".repeat(31));
+        let input = Day3::parse(&widest).unwrap();
+        assert_eq!(Day3::part_one(&input), 0);
+        assert_eq!(Day3::part_two(&input), 0);
+
+        // gamma 2^16 and epsilon 2^16 - 1, whose product needs more than a u32
+        let input =
+            Day3::parse("10000000000000000\n10000000000000000\n00000000000000001\n").unwrap();
+        assert_eq!(Day3::part_one(&input), 65536 * 65535);
+
+        let err = Day3::parse(&"1".repeat(32)).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            format!(
+                "<input>:1:1: expected a binary number of at most 31 digits, found '{}'",
+                "1".repeat(32)
+            )
+        );
     }
 }
@@ -1,8 +1,8 @@
-use std::fs;
-use std::io;
+use crate::error::{parse_token, ParseError};
+use crate::Solution;
 
 #[derive(Debug, Clone)]
-struct BingoTable {
+pub struct BingoTable {
     numbers: Vec<u32>,
     filled: Vec<bool>,
     row_filled: Vec<u32>,
@@ -12,30 +12,38 @@ struct BingoTable {
 }
 
 impl BingoTable {
-    fn from_str(mat: &str) -> BingoTable {
+    /// Parses the table `mat`, which is a part of `input`
+    fn from_str(input: &str, mat: &str) -> Result<BingoTable, ParseError> {
         let lines: Vec<_> = mat.lines().collect();
         let rows = lines.len();
-        let cols = lines[0].split(" ").filter(|x| x.len() > 0).count();
+        let cols = lines[0].split(" ").filter(|x| !x.is_empty()).count();
 
         let mut numbers: Vec<u32> = Vec::with_capacity(rows * cols);
 
         for line in lines {
-            let nums = line.split(" ").filter(|x| x.len() > 0);
+            let nums: Vec<_> = line.split(" ").filter(|x| !x.is_empty()).collect();
+            if nums.len() != cols {
+                return Err(ParseError::invalid(
+                    input,
+                    line,
+                    format!("a row of {} numbers", cols),
+                ));
+            }
             for num_str in nums {
-                numbers.push(num_str.parse().unwrap());
+                numbers.push(parse_token(input, num_str, "a number")?);
             }
         }
 
         let filled = vec![false; numbers.len()];
 
-        BingoTable {
-            numbers: numbers,
-            filled: filled,
+        Ok(BingoTable {
+            numbers,
+            filled,
             row_filled: vec![0; rows],
             col_filled: vec![0; cols],
-            rows: rows,
-            cols: cols,
-        }
+            rows,
+            cols,
+        })
     }
 
     fn get_pos(&self, index: usize) -> (usize, usize) {
@@ -43,10 +51,7 @@ impl BingoTable {
     }
 
     pub fn fill(&mut self, num: u32) -> Option<u32> {
-        let index = match self.numbers.iter().position(|x| *x == num) {
-            Some(pos) => pos,
-            None => return None,
-        };
+        let index = self.numbers.iter().position(|x| *x == num)?;
 
         let (row, col) = self.get_pos(index);
 
@@ -80,70 +85,71 @@ impl BingoTable {
     }
 }
 
-fn part_one(nums: &Vec<u32>, tables: &Vec<BingoTable>) -> u32 {
-    let mut tables = (*tables).clone();
-    for num in nums {
-        for table in tables.iter_mut() {
-            match table.fill(*num) {
-                Some(x) => return x,
-                None => (),
-            }
+pub struct Day4;
+
+impl Solution for Day4 {
+    const DAY: u8 = 4;
+
+    /// Drawn numbers and the bingo tables
+    type Input = (Vec<u32>, Vec<BingoTable>);
+    type AnswerOne = u32;
+    type AnswerTwo = u32;
+
+    fn parse(input: &str) -> Result<(Vec<u32>, Vec<BingoTable>), ParseError> {
+        let mut parts = input.split("\n\n");
+        let numbers: Vec<u32> = parts
+            .next()
+            .unwrap()
+            .split(",")
+            .map(|s| parse_token(input, s, "a number"))
+            .collect::<Result<_, _>>()?;
+
+        let matrices: Vec<_> = parts
+            .filter(|x| !x.trim().is_empty())
+            .map(|x| BingoTable::from_str(input, x))
+            .collect::<Result<_, _>>()?;
+
+        if matrices.is_empty() {
+            return Err(ParseError::missing(input, input, "a bingo table"));
         }
+
+        Ok((numbers, matrices))
     }
 
-    panic!("No board wins");
-}
+    fn part_one((nums, tables): &(Vec<u32>, Vec<BingoTable>)) -> u32 {
+        let mut tables = tables.clone();
+        for num in nums {
+            for table in tables.iter_mut() {
+                if let Some(x) = table.fill(*num) {
+                    return x;
+                }
+            }
+        }
+
+        panic!("No board wins");
+    }
 
-fn part_two(nums: &Vec<u32>, tables: &Vec<BingoTable>) -> u32 {
-    let mut tables = tables.clone();
-    let mut won = vec![false; tables.len()];
+    fn part_two((nums, tables): &(Vec<u32>, Vec<BingoTable>)) -> u32 {
+        let mut tables = tables.clone();
+        let mut won = vec![false; tables.len()];
 
-    for num in nums {
-        for (idx, table) in tables.iter_mut().enumerate() {
-            if won[idx] {
-                continue;
-            }
-            match table.fill(*num) {
-                Some(x) => {
+        for num in nums {
+            for (idx, table) in tables.iter_mut().enumerate() {
+                if won[idx] {
+                    continue;
+                }
+                if let Some(x) = table.fill(*num) {
                     if won.iter().filter(|&&x| !x).count() == 1 {
                         return x;
                     } else {
                         won[idx] = true;
                     }
                 }
-                None => (),
             }
         }
-    }
-
-    panic!("No board wins last");
-}
-
-fn parse(filename: &str) -> io::Result<(Vec<u32>, Vec<BingoTable>)> {
-    let file = fs::read_to_string(filename)?;
-    let mut parts = file.split("\n\n");
-    let numbers: Vec<u32> = parts
-        .next()
-        .unwrap()
-        .split(",")
-        .map(|s| s.parse().unwrap())
-        .collect();
-
-    let matrices = parts.map(|x| BingoTable::from_str(x)).collect();
-
-    Ok((numbers, matrices))
-}
 
-fn main() -> Result<(), Box<dyn std::error::Error + 'static>> {
-    let (nums, tables) = parse("inputs/day4")?;
-
-    let res = part_one(&nums, &tables);
-    println!("Result of part one is: {}", res);
-
-    let res = part_two(&nums, &tables);
-    println!("Result of part two is: {}", res);
-
-    Ok(())
+        panic!("No board wins last");
+    }
 }
 
 #[cfg(test)]
@@ -154,15 +160,24 @@ mod tests {
     fn test_matrix_parse() {
         let str =
             " 3 15  0  2 22 \n 9 18 13 17  5 \n19  8  7 25 23 \n20 11 10 24  4 \n14 21 16 12  6";
-        let matrix = BingoTable::from_str(str);
+        let matrix = BingoTable::from_str(str, str).unwrap();
         assert_eq!(matrix.rows, 5);
         assert_eq!(matrix.cols, 5);
         assert_eq!(matrix.get(2, 3), 25)
     }
 
+    #[test]
+    fn test_parse_error() {
+        let err = Day4::parse("1,2\n\n 1  2\n 3  x\n").unwrap_err();
+        assert_eq!(err.to_string(), "<input>:4:5: expected a number, found 'x'");
+
+        let err = Day4::parse("1,2\n\n 1  2\n 3\n").unwrap_err();
+        assert_eq!(err.location().line, 4);
+    }
+
     #[test]
     fn test_parse() {
-        let (nums, mat) = parse("inputs/day4_test").unwrap();
+        let (nums, mat) = Day4::parse_file("inputs/day4_test").unwrap();
 
         assert_eq!(
             nums,
@@ -178,17 +193,17 @@ mod tests {
 
     #[test]
     fn test_part_one() {
-        let (nums, tables) = parse("inputs/day4_test").unwrap();
+        let input = Day4::parse_file("inputs/day4_test").unwrap();
 
-        let res = part_one(&nums, &tables);
+        let res = Day4::part_one(&input);
         assert_eq!(res, 4512);
     }
 
     #[test]
     fn test_part_two() {
-        let (nums, tables) = parse("inputs/day4_test").unwrap();
+        let input = Day4::parse_file("inputs/day4_test").unwrap();
 
-        let res = part_two(&nums, &tables);
+        let res = Day4::part_two(&input);
         assert_eq!(res, 1924);
     }
 }
@@ -1,46 +1,55 @@
 use std::fmt::Display;
 use std::ops::{Index, IndexMut};
-use std::{fs, io};
 
+use crate::error::{parse_token, ParseError};
+use crate::grid::Grid;
+use crate::Solution;
+
+/// Number of vents per point, shifted so the smallest coordinates are at (0, 0)
 #[derive(Debug, Clone)]
-struct Field {
-    numbers: Vec<u32>,
-    pub rows: usize,
-    pub cols: usize,
+pub struct Field {
+    grid: Grid<u32>,
     min_x: i32,
     min_y: i32,
 }
 
 #[derive(Debug)]
-struct Point {
+pub struct Point {
     pub x: i32,
     pub y: i32,
 }
 
 #[derive(Debug)]
-struct Line {
+pub struct Line {
     pub p1: Point,
     pub p2: Point,
 }
 
+impl Point {
+    ///  s in the form 'x,y', a part of `input`
+    fn from_str(input: &str, s: &str) -> Result<Point, ParseError> {
+        let (x, y) = s
+            .split_once(",")
+            .ok_or_else(|| ParseError::missing(input, s, "',y'"))?;
+
+        Ok(Point {
+            x: parse_token(input, x, "a coordinate")?,
+            y: parse_token(input, y, "a coordinate")?,
+        })
+    }
+}
+
 impl Line {
-    ///  s in the form 'x1,y1 -> x2,y2
-    fn from_str(s: &str) -> Line {
-        let nums: Vec<Vec<i32>> = s
-            .split(" -> ")
-            .map(|x| x.split(",").map(|x| x.parse().unwrap()).collect())
-            .collect();
-
-        Line {
-            p1: Point {
-                x: nums[0][0],
-                y: nums[0][1],
-            },
-            p2: Point {
-                x: nums[1][0],
-                y: nums[1][1],
-            },
-        }
+    ///  s in the form 'x1,y1 -> x2,y2', a part of `input`
+    fn from_str(input: &str, s: &str) -> Result<Line, ParseError> {
+        let (p1, p2) = s
+            .split_once(" -> ")
+            .ok_or_else(|| ParseError::missing(input, s, "' -> x2,y2'"))?;
+
+        Ok(Line {
+            p1: Point::from_str(input, p1)?,
+            p2: Point::from_str(input, p2)?,
+        })
     }
 }
 
@@ -53,27 +62,25 @@ impl Field {
         }
     }
 
-    fn from_str(lines: &str, diagonal: bool) -> Field {
-        let lines: Vec<_> = lines.lines().map(|l| Line::from_str(l)).collect();
+    fn from_lines(lines: &[Line], diagonal: bool) -> Field {
         let (mut min_x, mut min_y, mut max_x, mut max_y) = (0i32, 0i32, 0i32, 0i32);
-        for line in &lines {
+        for line in lines {
             Self::update_min_max(line.p1.x, &mut min_x, &mut max_x);
             Self::update_min_max(line.p2.x, &mut min_x, &mut max_x);
             Self::update_min_max(line.p1.y, &mut min_y, &mut max_y);
             Self::update_min_max(line.p2.y, &mut min_y, &mut max_y);
         }
 
-        let numbers = vec![0; ((max_x - min_x + 1) * (max_y - min_y + 1)) as usize];
+        let width = (max_x - min_x) as usize + 1;
+        let height = (max_y - min_y) as usize + 1;
         let mut field = Field {
-            numbers,
-            rows: (max_y - min_y) as usize + 1,
-            cols: (max_x - min_x) as usize + 1,
+            grid: Grid::new(width, height, 0),
             min_x,
             min_y,
         };
 
         for line in lines {
-            field.add_line(&line, diagonal);
+            field.add_line(line, diagonal);
         }
 
         field
@@ -104,13 +111,8 @@ impl Field {
         }
     }
 
-    #[allow(dead_code)]
-    fn get_pos(&self, index: usize) -> (usize, usize) {
-        (index / self.cols, index % self.cols)
-    }
-
-    fn get_index(&self, x: i32, y: i32) -> usize {
-        self.cols * (x - self.min_x) as usize + (y - self.min_y) as usize
+    fn grid_pos(&self, x: i32, y: i32) -> (usize, usize) {
+        ((x - self.min_x) as usize, (y - self.min_y) as usize)
     }
 }
 
@@ -118,49 +120,55 @@ impl Index<(i32, i32)> for Field {
     type Output = u32;
 
     fn index(&self, index: (i32, i32)) -> &Self::Output {
-        &self.numbers[self.get_index(index.0, index.1)]
+        &self.grid[self.grid_pos(index.0, index.1)]
     }
 }
 
 impl IndexMut<(i32, i32)> for Field {
     fn index_mut(&mut self, index: (i32, i32)) -> &mut Self::Output {
-        let index = self.get_index(index.0, index.1);
-        &mut self.numbers[index]
+        let pos = self.grid_pos(index.0, index.1);
+        &mut self.grid[pos]
     }
 }
 
 impl Display for Field {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        let s: String = self
-            .numbers
-            .chunks(self.cols)
-            .map(|x| x.iter().map(|x| format!("{}", x)).collect::<String>() + "\n")
-            .collect();
+        let grid = self.grid.map(|&n| match n {
+            0 => String::from("."),
+            n => n.to_string(),
+        });
 
-        write!(f, "{}", s.replace("0", "."))
+        write!(f, "{}", grid)
     }
 }
 
-fn parse(filename: &str, diagonal: bool) -> io::Result<Field> {
-    let file = fs::read_to_string(filename)?;
-
-    Ok(Field::from_str(&file, diagonal))
+fn count_overlaps(field: &Field) -> usize {
+    field.grid.iter().filter(|&&x| x >= 2).count()
 }
 
-fn part(field: &Field) -> usize {
-    field.numbers.iter().filter(|&&x| x >= 2).count()
-}
+pub struct Day5;
+
+impl Solution for Day5 {
+    const DAY: u8 = 5;
 
-fn main() -> Result<(), Box<dyn std::error::Error + 'static>> {
-    let field = parse("inputs/day5", false)?;
-    let sol = part(&field);
-    println!("Solution part one: {}", sol);
+    type Input = Vec<Line>;
+    type AnswerOne = usize;
+    type AnswerTwo = usize;
+
+    fn parse(input: &str) -> Result<Vec<Line>, ParseError> {
+        input
+            .lines()
+            .map(|line| Line::from_str(input, line))
+            .collect()
+    }
 
-    let field = parse("inputs/day5", true)?;
-    let sol = part(&field);
-    println!("Solution part two: {}", sol);
+    fn part_one(lines: &Vec<Line>) -> usize {
+        count_overlaps(&Field::from_lines(lines, false))
+    }
 
-    Ok(())
+    fn part_two(lines: &Vec<Line>) -> usize {
+        count_overlaps(&Field::from_lines(lines, true))
+    }
 }
 
 #[cfg(test)]
@@ -169,24 +177,25 @@ mod tests {
 
     #[test]
     fn test_input() {
-        let field = parse("inputs/day5_test", false).unwrap();
+        let lines = Day5::parse_file("inputs/day5_test").unwrap();
+        let field = Field::from_lines(&lines, false);
         println!("{}", field);
-        assert_eq!(field.cols, 10);
-        assert_eq!(field.rows, 10);
-        assert_eq!(field.numbers[0], 0);
+        assert_eq!(field.grid.width(), 10);
+        assert_eq!(field.grid.height(), 10);
+        assert_eq!(field[(0, 0)], 0);
     }
 
     #[test]
     fn test_part_one() {
-        let field = parse("inputs/day5_test", false).unwrap();
-        println!("{}", field);
-        assert_eq!(part(&field), 5);
+        let lines = Day5::parse_file("inputs/day5_test").unwrap();
+        println!("{}", Field::from_lines(&lines, false));
+        assert_eq!(Day5::part_one(&lines), 5);
     }
 
     #[test]
     fn test_part_two() {
-        let field = parse("inputs/day5_test", true).unwrap();
-        println!("{}", field);
-        assert_eq!(part(&field), 12);
+        let lines = Day5::parse_file("inputs/day5_test").unwrap();
+        println!("{}", Field::from_lines(&lines, true));
+        assert_eq!(Day5::part_two(&lines), 12);
     }
 }
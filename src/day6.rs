@@ -1,42 +1,274 @@
-use std::fs;
+use std::error::Error;
+use std::fmt::{self, Display};
 
-fn parse(filename: &str) -> std::io::Result<Vec<usize>> {
-    let mut states = vec![0; 9];
-    for i in fs::read_to_string(filename)?
-        .split(",")
-        .map(|x| x.parse::<usize>().unwrap())
-    {
-        states[i] += 1;
+use crate::bigint::BigUint;
+use crate::error::{parse_token, ParseError};
+use crate::Solution;
+
+/// `M` with `M * fish` being the fish one step later
+type Matrix<T> = Vec<Vec<T>>;
+
+/// The fish on `day` are too many to count in a `usize`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Overflow {
+    pub day: u32,
+}
+
+impl Display for Overflow {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "the number of fish overflows on day {}", self.day)
     }
+}
 
-    Ok(states)
+impl Error for Overflow {}
+
+/// How fish with a timer reproduce
+///
+/// Every step the timers count down. A fish at timer 0 resets to `reset`
+/// and gets `offspring` new fish starting at `newborn`. The fish are
+/// counted per timer value, from 0 to [`Population::states`] - 1.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Population {
+    pub reset: usize,
+    pub newborn: usize,
+    pub offspring: usize,
 }
 
-fn step(fish: &mut Vec<usize>) {
-    let mut save = fish[0];
-    for i in (0..9).rev() {
-        let other_save = fish[i];
-        fish[i] = save;
-        save = other_save;
+impl Default for Population {
+    /// The lanternfish of the puzzle
+    fn default() -> Population {
+        Population::new(6, 8, 1)
     }
-    fish[6] += save;
 }
 
-fn n_step(fish: &mut Vec<usize>, n: u32) {
-    for _ in 0..n {
-        step(fish);
+impl Population {
+    pub fn new(reset: usize, newborn: usize, offspring: usize) -> Population {
+        Population {
+            reset,
+            newborn,
+            offspring,
+        }
+    }
+
+    /// Number of timer values a fish can have
+    pub fn states(&self) -> usize {
+        self.reset.max(self.newborn) + 1
+    }
+
+    pub fn step(&self, fish: &mut [usize]) {
+        assert_eq!(fish.len(), self.states(), "one count per timer value");
+
+        let spawning = fish[0];
+        fish.rotate_left(1);
+        fish[self.states() - 1] = 0;
+        fish[self.reset] += spawning;
+        fish[self.newborn] += spawning * self.offspring;
+    }
+
+    /// Same as [`Population::step`], but `None` if a count or the total
+    /// overflows, in which case `fish` is left as it was
+    pub fn checked_step(&self, fish: &mut [usize]) -> Option<()> {
+        assert_eq!(fish.len(), self.states(), "one count per timer value");
+
+        let spawning = fish[0];
+        let mut next = fish.to_vec();
+        next.rotate_left(1);
+        next[self.states() - 1] = 0;
+        next[self.reset] = next[self.reset].checked_add(spawning)?;
+        next[self.newborn] =
+            next[self.newborn].checked_add(spawning.checked_mul(self.offspring)?)?;
+        next.iter()
+            .try_fold(0usize, |total, &f| total.checked_add(f))?;
+
+        fish.copy_from_slice(&next);
+        Some(())
+    }
+
+    pub fn n_step(&self, fish: &mut [usize], n: u32) {
+        for _ in 0..n {
+            self.step(fish);
+        }
+    }
+
+    /// The counts per timer value on each day from 0 to `n`
+    ///
+    /// The counts are `usize`, so this is meant for a few hundred days.
+    /// Fails with the first day on which a count or the total overflows.
+    pub fn series(&self, fish: &[usize], n: u32) -> Result<Vec<Vec<usize>>, Overflow> {
+        let mut fish = fish.to_vec();
+        let mut series = vec![fish.clone()];
+        for day in 1..=n {
+            self.checked_step(&mut fish).ok_or(Overflow { day })?;
+            series.push(fish.clone());
+        }
+        Ok(series)
+    }
+
+    /// The transition matrix of [`Population::step`]
+    fn transition<T>(&self, convert: impl Fn(usize) -> T) -> Matrix<T> {
+        let states = self.states();
+        // column j is where a single fish with timer j goes
+        let columns: Vec<Vec<usize>> = (0..states)
+            .map(|j| {
+                let mut fish = vec![0; states];
+                fish[j] = 1;
+                self.step(&mut fish);
+                fish
+            })
+            .collect();
+
+        (0..states)
+            .map(|i| (0..states).map(|j| convert(columns[j][i])).collect())
+            .collect()
+    }
+
+    /// Same as [`Population::n_step`], but in O(log n) matrix multiplications
+    ///
+    /// The counts grow exponentially, so they are exact big integers. Their
+    /// digits grow linearly with `n`, which makes a million days take seconds;
+    /// [`Population::n_step_mod`] stays fast for any `n`.
+    pub fn n_step_fast(&self, fish: &[usize], n: u64) -> Vec<BigUint> {
+        assert_eq!(fish.len(), self.states(), "one count per timer value");
+
+        let matrix = pow(&self.transition(BigUint::from), n, mul);
+        matrix
+            .iter()
+            .map(|row| {
+                row.iter()
+                    .zip(fish)
+                    .map(|(m, &f)| m * &BigUint::from(f))
+                    .sum()
+            })
+            .collect()
+    }
+
+    /// Same as [`Population::n_step_fast`], but with the counts modulo `modulus`
+    ///
+    /// Stays in machine integers, so even a billion days take microseconds.
+    pub fn n_step_mod(&self, fish: &[usize], n: u64, modulus: u64) -> Vec<u64> {
+        assert_eq!(fish.len(), self.states(), "one count per timer value");
+        assert!(modulus > 0, "the modulus has to be positive");
+
+        let transition = self.transition(|count| count as u64 % modulus);
+        let matrix = pow(&transition, n, |a, b| mul_mod(a, b, modulus));
+        matrix
+            .iter()
+            .map(|row| {
+                let sum = row
+                    .iter()
+                    .zip(fish)
+                    .map(|(&m, &f)| m as u128 * f as u128 % modulus as u128)
+                    .sum::<u128>();
+                (sum % modulus as u128) as u64
+            })
+            .collect()
     }
 }
 
-fn main() -> Result<(), Box<dyn std::error::Error + 'static>> {
-    let mut fish = parse("inputs/day6")?;
-    n_step(&mut fish, 80);
-    println!("Answer part 1: {}", fish.iter().sum::<usize>());
+/// A `series` as CSV with the day and the total, and with `buckets`
+/// the count per timer value as well
+pub fn series_csv(series: &[Vec<usize>], buckets: bool) -> String {
+    let states = series.first().map_or(0, Vec::len);
+    let mut csv = String::from("day,total");
+    if buckets {
+        for timer in 0..states {
+            csv.push_str(&format!(",timer{}", timer));
+        }
+    }
+    csv.push('\n');
 
-    n_step(&mut fish, 256 - 80);
-    println!("Answer part 2: {}", fish.iter().sum::<usize>());
+    for (day, fish) in series.iter().enumerate() {
+        csv.push_str(&format!("{},{}", day, fish.iter().sum::<usize>()));
+        if buckets {
+            for count in fish {
+                csv.push_str(&format!(",{}", count));
+            }
+        }
+        csv.push('\n');
+    }
+    csv
+}
 
-    Ok(())
+fn mul(a: &Matrix<BigUint>, b: &Matrix<BigUint>) -> Matrix<BigUint> {
+    let n = a.len();
+    (0..n)
+        .map(|i| {
+            (0..n)
+                .map(|j| (0..n).map(|k| &a[i][k] * &b[k][j]).sum())
+                .collect()
+        })
+        .collect()
+}
+
+/// Product of matrices with entries below `modulus`, modulo `modulus`
+fn mul_mod(a: &Matrix<u64>, b: &Matrix<u64>, modulus: u64) -> Matrix<u64> {
+    let n = a.len();
+    (0..n)
+        .map(|i| {
+            (0..n)
+                .map(|j| {
+                    let sum = (0..n)
+                        .map(|k| a[i][k] as u128 * b[k][j] as u128 % modulus as u128)
+                        .sum::<u128>();
+                    (sum % modulus as u128) as u64
+                })
+                .collect()
+        })
+        .collect()
+}
+
+/// `matrix` to the power of `n`, by squaring with `mul`
+fn pow<T: Clone + From<u64>>(
+    matrix: &Matrix<T>,
+    mut n: u64,
+    mul: impl Fn(&Matrix<T>, &Matrix<T>) -> Matrix<T>,
+) -> Matrix<T> {
+    let size = matrix.len();
+    let mut result: Matrix<T> = (0..size)
+        .map(|i| (0..size).map(|j| T::from((i == j) as u64)).collect())
+        .collect();
+
+    let mut square = matrix.clone();
+    while n > 0 {
+        if n % 2 == 1 {
+            result = mul(&result, &square);
+        }
+        square = mul(&square, &square);
+        n /= 2;
+    }
+    result
+}
+
+pub struct Day6;
+
+impl Solution for Day6 {
+    const DAY: u8 = 6;
+
+    /// Number of fish per timer value
+    type Input = Vec<usize>;
+    type AnswerOne = BigUint;
+    type AnswerTwo = BigUint;
+
+    fn parse(input: &str) -> Result<Vec<usize>, ParseError> {
+        let mut states = vec![0; Population::default().states()];
+        for x in input.trim().split(",") {
+            let i: usize = parse_token(input, x, "a timer from 0 to 8")?;
+            match states.get_mut(i) {
+                Some(count) => *count += 1,
+                None => return Err(ParseError::invalid(input, x, "a timer from 0 to 8")),
+            }
+        }
+
+        Ok(states)
+    }
+
+    fn part_one(fish: &Vec<usize>) -> BigUint {
+        Population::default().n_step_fast(fish, 80).iter().sum()
+    }
+
+    fn part_two(fish: &Vec<usize>) -> BigUint {
+        Population::default().n_step_fast(fish, 256).iter().sum()
+    }
 }
 
 #[cfg(test)]
@@ -45,30 +277,142 @@ mod tests {
 
     #[test]
     fn test_input() {
-        let input = parse("inputs/day6_test").unwrap();
+        let input = Day6::parse_file("inputs/day6_test").unwrap();
         assert_eq!(input, vec![0, 1, 1, 2, 1, 0, 0, 0, 0]);
     }
 
     #[test]
     fn test_step() {
-        let mut input = parse("inputs/day6_test").unwrap();
-        step(&mut input);
+        let fish = Population::default();
+        let mut input = Day6::parse_file("inputs/day6_test").unwrap();
+        fish.step(&mut input);
         assert_eq!(input, vec![1, 1, 2, 1, 0, 0, 0, 0, 0]);
-        step(&mut input);
+        fish.step(&mut input);
         assert_eq!(input, vec![1, 2, 1, 0, 0, 0, 1, 0, 1]);
     }
 
+    #[test]
+    fn test_n_step_fast() {
+        let start = Day6::parse_file("inputs/day6_test").unwrap();
+        let populations = [
+            Population::default(),
+            Population::new(6, 8, 3),
+            // newborns start below the reset timer
+            Population::new(4, 1, 2),
+        ];
+        for population in populations {
+            let mut fish = start.clone();
+            fish.resize(population.states(), 0);
+            let first = fish.clone();
+            for n in 0..100 {
+                let expected: Vec<_> = fish.iter().map(|&f| BigUint::from(f)).collect();
+                assert_eq!(population.n_step_fast(&first, n), expected);
+                population.step(&mut fish);
+            }
+        }
+    }
+
+    #[test]
+    fn test_n_step_mod() {
+        const MODULUS: u64 = 1_000_000_007;
+        let fish = Day6::parse_file("inputs/day6_test").unwrap();
+        let population = Population::default();
+        for n in [0, 1, 18, 256, 500, 1000] {
+            let exact = population.n_step_fast(&fish, n);
+            // the remainder of the decimal digits
+            let expected: Vec<_> = exact
+                .iter()
+                .map(|count| {
+                    count
+                        .to_string()
+                        .bytes()
+                        .fold(0, |r, d| (r * 10 + (d - b'0') as u64) % MODULUS)
+                })
+                .collect();
+            assert_eq!(population.n_step_mod(&fish, n, MODULUS), expected);
+        }
+
+        let billion = population.n_step_mod(&fish, 1_000_000_000, MODULUS);
+        assert_eq!(billion.iter().sum::<u64>() % MODULUS, 249_911_329);
+        assert_eq!(billion[0], 388_552_115);
+    }
+
+    #[test]
+    fn test_population() {
+        let rabbits = Population::new(2, 3, 2);
+        assert_eq!(rabbits.states(), 4);
+
+        let mut fish = vec![1, 0, 0, 0];
+        rabbits.step(&mut fish);
+        assert_eq!(fish, vec![0, 0, 1, 2]);
+        rabbits.n_step(&mut fish, 2);
+        assert_eq!(fish, vec![1, 2, 0, 0]);
+        rabbits.step(&mut fish);
+        assert_eq!(fish, vec![2, 0, 1, 2]);
+    }
+
+    #[test]
+    fn test_long_run() {
+        let fish = Day6::parse_file("inputs/day6_test").unwrap();
+        // far beyond usize, which overflows on day 490 for this input
+        let total: BigUint = Population::default()
+            .n_step_fast(&fish, 10_000)
+            .iter()
+            .sum();
+        let digits = total.to_string();
+        assert_eq!(digits.len(), 380);
+        assert!(digits.starts_with("12295220913725202160"));
+        assert!(digits.ends_with("15511445328619725056"));
+    }
+
+    #[test]
+    fn test_series() {
+        let fish = Day6::parse_file("inputs/day6_test").unwrap();
+        let series = Population::default().series(&fish, 18).unwrap();
+        assert_eq!(series.len(), 19);
+        assert_eq!(series[0], fish);
+        assert_eq!(series[2], vec![1, 2, 1, 0, 0, 0, 1, 0, 1]);
+        assert_eq!(series[18].iter().sum::<usize>(), 26);
+
+        let csv = series_csv(&series, false);
+        assert!(csv.starts_with("day,total\n0,5\n1,5\n2,6\n"));
+        assert!(csv.ends_with("18,26\n"));
+
+        let csv = series_csv(&series[..2], true);
+        assert_eq!(
+            csv,
+            "day,total,timer0,timer1,timer2,timer3,timer4,timer5,timer6,timer7,timer8\n\
+             0,5,0,1,1,2,1,0,0,0,0\n\
+             1,5,1,1,2,1,0,0,0,0,0\n"
+        );
+    }
+
+    #[test]
+    fn test_series_overflow() {
+        let fish = Day6::parse_file("inputs/day6_test").unwrap();
+        let population = Population::default();
+        // the total is the first to overflow a 64 bit usize
+        let series = population.series(&fish, 489).unwrap();
+        assert_eq!(
+            series[489].iter().sum::<usize>(),
+            17_614_907_331_943_978_900
+        );
+        assert_eq!(population.series(&fish, 500), Err(Overflow { day: 490 }));
+
+        let mut fish = vec![usize::MAX, 0, 0, 0, 0, 0, 0, 0, 0];
+        assert_eq!(population.checked_step(&mut fish), None);
+        assert_eq!(fish, vec![usize::MAX, 0, 0, 0, 0, 0, 0, 0, 0]);
+    }
+
     #[test]
     fn test_part_one() {
-        let mut fish = parse("inputs/day6_test").unwrap();
-        n_step(&mut fish, 80);
-        assert_eq!(fish.iter().sum::<usize>(), 5934);
+        let fish = Day6::parse_file("inputs/day6_test").unwrap();
+        assert_eq!(Day6::part_one(&fish), BigUint::from(5934usize));
     }
 
     #[test]
     fn test_part_two() {
-        let mut fish = parse("inputs/day6_test").unwrap();
-        n_step(&mut fish, 256);
-        assert_eq!(fish.iter().sum::<usize>(), 26984457539);
+        let fish = Day6::parse_file("inputs/day6_test").unwrap();
+        assert_eq!(Day6::part_two(&fish), BigUint::from(26984457539usize));
     }
 }
@@ -1,43 +1,249 @@
-use std::fs;
+use std::cmp::Ordering;
 
-fn parse(filename: &str) -> std::io::Result<Vec<usize>> {
-    Ok(fs::read_to_string(filename)?
-        .split(",")
-        .map(|x| x.parse::<usize>().unwrap())
-        .collect())
+use crate::error::{parse_token, ParseError};
+use crate::Solution;
+
+/// Fuel a crab needs to move a distance
+pub trait FuelCost {
+    fn cost(&self, distance: usize) -> usize;
+
+    /// Whether the cost is convex in the distance, which allows a ternary search
+    ///
+    /// There is no default, as a wrong `true` silently gives wrong answers.
+    fn is_convex(&self) -> bool;
 }
 
-fn part_one(pos: &Vec<usize>) -> usize {
-    let max = *pos.iter().max().unwrap();
-    (0..max)
-        .map(|i| pos.iter().map(move |&j| i.max(j) - i.min(j)).sum())
-        .min()
+/// One fuel per step
+pub struct Linear;
+
+/// One more fuel for every further step, so `n * (n + 1) / 2`
+pub struct Triangular;
+
+/// The square of the distance
+pub struct Quadratic;
+
+/// Like [`Linear`], but never more than the cap
+pub struct Capped(pub usize);
+
+impl FuelCost for Linear {
+    fn cost(&self, distance: usize) -> usize {
+        distance
+    }
+
+    fn is_convex(&self) -> bool {
+        true
+    }
+}
+
+impl FuelCost for Triangular {
+    fn cost(&self, distance: usize) -> usize {
+        distance * (distance + 1) / 2
+    }
+
+    fn is_convex(&self) -> bool {
+        true
+    }
+}
+
+impl FuelCost for Quadratic {
+    fn cost(&self, distance: usize) -> usize {
+        distance * distance
+    }
+
+    fn is_convex(&self) -> bool {
+        true
+    }
+}
+
+impl FuelCost for Capped {
+    fn cost(&self, distance: usize) -> usize {
+        distance.min(self.0)
+    }
+
+    /// Flat after the cap, so the total can have several local minima
+    fn is_convex(&self) -> bool {
+        false
+    }
+}
+
+/// Where the crabs meet and the fuel they need for it
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Alignment {
+    pub position: usize,
+    pub fuel: usize,
+}
+
+/// Fuel for all crabs to move to `target`
+fn total(pos: &[usize], cost: &impl FuelCost, target: usize) -> usize {
+    pos.iter().map(|&p| cost.cost(p.abs_diff(target))).sum()
+}
+
+/// The cheapest of `targets`, the leftmost one on a tie
+fn cheapest(
+    pos: &[usize],
+    cost: &impl FuelCost,
+    targets: impl Iterator<Item = usize>,
+) -> Alignment {
+    targets
+        .map(|position| Alignment {
+            position,
+            fuel: total(pos, cost, position),
+        })
+        .min_by_key(|a| (a.fuel, a.position))
         .unwrap()
 }
 
-fn part_two(pos: &Vec<usize>) -> usize {
+/// Best alignment, by trying every position
+pub fn brute_force(pos: &[usize], cost: &impl FuelCost) -> Alignment {
     let max = *pos.iter().max().unwrap();
-    let ret: usize = (0..max)
-        .map(|i| {
-            pos.iter()
-                .map(move |&j| {
-                    let n = i.max(j) - i.min(j);
-                    return n * (n + 1);
-                })
-                .sum()
-        })
-        .min()
-        .unwrap();
-    ret / 2
+    cheapest(pos, cost, 0..=max)
 }
 
-fn main() -> Result<(), Box<dyn std::error::Error + 'static>> {
-    let input = parse("inputs/day7").unwrap();
+/// Best alignment, with a ternary search for convex costs
+///
+/// Other costs fall back to the brute force. Like it, this returns the
+/// leftmost position when several are equally cheap.
+pub fn align(pos: &[usize], cost: &impl FuelCost) -> Alignment {
+    if !cost.is_convex() {
+        return brute_force(pos, cost);
+    }
 
-    println!("Answer part one: {}", part_one(&input));
-    println!("Answer part two: {}", part_two(&input));
+    let (mut low, mut high) = (*pos.iter().min().unwrap(), *pos.iter().max().unwrap());
+    while high - low > 2 {
+        let third = (high - low) / 3;
+        let (left, right) = (low + third, high - third);
+        // the total is convex, so on a tie the minimum lies between them
+        // or is flat, and either way the leftmost one is left of `right`
+        match total(pos, cost, left).cmp(&total(pos, cost, right)) {
+            Ordering::Less | Ordering::Equal => high = right - 1,
+            Ordering::Greater => low = left + 1,
+        }
+    }
+    cheapest(pos, cost, low..=high)
+}
+
+/// Best alignment with [`Linear`] cost, which is at the median
+pub fn median_linear(pos: &[usize]) -> Alignment {
+    let mut sorted = pos.to_vec();
+    sorted.sort_unstable();
+    let median = sorted[(sorted.len() - 1) / 2];
+    Alignment {
+        position: median,
+        fuel: total(pos, &Linear, median),
+    }
+}
 
-    Ok(())
+/// Best alignment with [`Triangular`] cost
+///
+/// The best position is within 1/2 of the mean, so only the positions
+/// around it are tried.
+pub fn mean_triangular(pos: &[usize]) -> Alignment {
+    let mean = pos.iter().sum::<usize>() / pos.len();
+    cheapest(pos, &Triangular, mean.saturating_sub(1)..=mean + 1)
+}
+
+/// Where the crabs meet when they can split up into groups
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Meeting {
+    /// Meeting points, sorted
+    pub points: Vec<usize>,
+    /// Fuel for every crab to go to its nearest point
+    pub fuel: usize,
+}
+
+/// Best `k` meeting points with [`Linear`] cost, every crab having weight 1
+pub fn k_median(pos: &[usize], k: usize) -> Meeting {
+    weighted_k_median(pos, &vec![1; pos.len()], k)
+}
+
+/// Best `k` meeting points with [`Linear`] cost times the crab's weight
+///
+/// Crabs going to the same point are neighbours when sorted by position,
+/// so the dynamic program splits the sorted crabs into at most `k` groups,
+/// each meeting at its weighted median. Takes O(k n^2 log n).
+pub fn weighted_k_median(pos: &[usize], weights: &[usize], k: usize) -> Meeting {
+    assert_eq!(pos.len(), weights.len(), "one weight per crab");
+    assert!(k > 0 && !pos.is_empty(), "need a crab and a meeting point");
+
+    let mut crabs: Vec<(usize, usize)> = pos.iter().copied().zip(weights.iter().copied()).collect();
+    crabs.sort_unstable();
+    let n = crabs.len();
+
+    // prefix sums of the weights and of weight * position
+    let mut weight_sum = vec![0; n + 1];
+    let mut moment_sum = vec![0; n + 1];
+    for (i, &(p, w)) in crabs.iter().enumerate() {
+        weight_sum[i + 1] = weight_sum[i] + w;
+        moment_sum[i + 1] = moment_sum[i] + w * p;
+    }
+
+    // weighted median of the crabs i..j and the fuel to get there
+    let group = |i: usize, j: usize| -> (usize, usize) {
+        let half = weight_sum[i] + (weight_sum[j] - weight_sum[i]).div_ceil(2);
+        let m = (weight_sum[i + 1..j].partition_point(|&w| w < half) + i).min(j - 1);
+        let median = crabs[m].0;
+        let left =
+            median * (weight_sum[m + 1] - weight_sum[i]) - (moment_sum[m + 1] - moment_sum[i]);
+        let right =
+            (moment_sum[j] - moment_sum[m + 1]) - median * (weight_sum[j] - weight_sum[m + 1]);
+        (median, left + right)
+    };
+
+    // best[c][j]: fuel for the first j crabs in c groups, with where the last group starts
+    let groups = k.min(n);
+    let mut best = vec![vec![(usize::MAX, 0); n + 1]; groups + 1];
+    best[0][0] = (0, 0);
+    for c in 1..=groups {
+        for j in c..=n {
+            best[c][j] = (c - 1..j)
+                .filter(|&i| best[c - 1][i].0 != usize::MAX)
+                .map(|i| (best[c - 1][i].0 + group(i, j).1, i))
+                .min()
+                .unwrap();
+        }
+    }
+
+    let mut points = Vec::new();
+    let (mut c, mut j) = (groups, n);
+    while c > 0 {
+        let i = best[c][j].1;
+        points.push(group(i, j).0);
+        (c, j) = (c - 1, i);
+    }
+    points.reverse();
+    points.dedup();
+
+    Meeting {
+        points,
+        fuel: best[groups][n].0,
+    }
+}
+
+pub struct Day7;
+
+impl Solution for Day7 {
+    const DAY: u8 = 7;
+
+    /// Horizontal positions of the crabs
+    type Input = Vec<usize>;
+    type AnswerOne = usize;
+    type AnswerTwo = usize;
+
+    fn parse(input: &str) -> Result<Vec<usize>, ParseError> {
+        input
+            .trim()
+            .split(",")
+            .map(|x| parse_token(input, x, "a position"))
+            .collect()
+    }
+
+    fn part_one(pos: &Vec<usize>) -> usize {
+        median_linear(pos).fuel
+    }
+
+    fn part_two(pos: &Vec<usize>) -> usize {
+        mean_triangular(pos).fuel
+    }
 }
 
 #[cfg(test)]
@@ -46,19 +252,143 @@ mod tests {
 
     #[test]
     fn test_input() {
-        let input = parse("inputs/day7_test").unwrap();
+        let input = Day7::parse_file("inputs/day7_test").unwrap();
         assert_eq!(input, vec![16, 1, 2, 0, 4, 2, 7, 1, 2, 14]);
     }
 
     #[test]
     fn test_part_one() {
-        let input = parse("inputs/day7_test").unwrap();
-        assert_eq!(part_one(&input), 37);
+        let input = Day7::parse_file("inputs/day7_test").unwrap();
+        assert_eq!(Day7::part_one(&input), 37);
     }
 
     #[test]
     fn test_part_two() {
-        let input = parse("inputs/day7_test").unwrap();
-        assert_eq!(part_two(&input), 168);
+        let input = Day7::parse_file("inputs/day7_test").unwrap();
+        assert_eq!(Day7::part_two(&input), 168);
+    }
+
+    /// Positions from a simple linear congruential generator
+    fn random_positions(seed: u64, len: usize, max: u64) -> Vec<usize> {
+        let mut state = seed;
+        (0..len)
+            .map(|_| {
+                state = state
+                    .wrapping_mul(6364136223846793005)
+                    .wrapping_add(1442695040888963407);
+                ((state >> 33) % max) as usize
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_against_brute_force() {
+        let mut inputs = vec![Day7::parse_file("inputs/day7_test").unwrap()];
+        inputs.extend((0..50).map(|seed| random_positions(seed, 1 + seed as usize * 3, 200)));
+
+        for pos in &inputs {
+            let linear = brute_force(pos, &Linear);
+            let triangular = brute_force(pos, &Triangular);
+            assert_eq!(median_linear(pos), linear);
+            assert_eq!(mean_triangular(pos), triangular);
+
+            assert_eq!(align(pos, &Linear), linear);
+            assert_eq!(align(pos, &Triangular), triangular);
+            assert_eq!(align(pos, &Quadratic), brute_force(pos, &Quadratic));
+            assert_eq!(align(pos, &Capped(20)), brute_force(pos, &Capped(20)));
+        }
+
+        // the best position can be the largest one
+        assert_eq!(brute_force(&[5, 5, 0], &Linear).position, 5);
+        // every position in between is as cheap, the leftmost one wins
+        let flat = Alignment {
+            position: 0,
+            fuel: 10,
+        };
+        assert_eq!(brute_force(&[0, 10], &Linear), flat);
+        assert_eq!(align(&[0, 10], &Linear), flat);
+    }
+
+    #[test]
+    fn test_positions() {
+        let input = Day7::parse_file("inputs/day7_test").unwrap();
+        assert_eq!(
+            median_linear(&input),
+            Alignment {
+                position: 2,
+                fuel: 37
+            }
+        );
+        assert_eq!(
+            align(&input, &Triangular),
+            Alignment {
+                position: 5,
+                fuel: 168
+            }
+        );
+    }
+
+    /// Best `k` (1 or 2) points by trying all of them
+    fn brute_force_k(pos: &[usize], weights: &[usize], k: usize) -> usize {
+        let max = *pos.iter().max().unwrap();
+        let fuel = |a: usize, b: usize| -> usize {
+            pos.iter()
+                .zip(weights)
+                .map(|(&p, &w)| w * p.abs_diff(a).min(p.abs_diff(b)))
+                .sum()
+        };
+        match k {
+            1 => (0..=max).map(|a| fuel(a, a)).min().unwrap(),
+            _ => (0..=max)
+                .flat_map(|a| (a..=max).map(move |b| (a, b)))
+                .map(|(a, b)| fuel(a, b))
+                .min()
+                .unwrap(),
+        }
+    }
+
+    #[test]
+    fn test_k_median() {
+        let input = Day7::parse_file("inputs/day7_test").unwrap();
+        let one = k_median(&input, 1);
+        assert_eq!(one.fuel, Day7::part_one(&input));
+        assert_eq!(one.points, vec![2]);
+
+        let two = k_median(&input, 2);
+        assert_eq!(two.fuel, brute_force_k(&input, &[1; 10], 2));
+        assert_eq!(k_median(&input, 10).fuel, 0);
+        assert_eq!(k_median(&input, 20).points.len(), 7);
+
+        for seed in 0..30 {
+            let pos = random_positions(seed, 1 + seed as usize, 60);
+            let weights = random_positions(seed + 100, pos.len(), 5);
+            assert_eq!(k_median(&pos, 1).fuel, median_linear(&pos).fuel);
+            for k in 1..=2 {
+                assert_eq!(
+                    weighted_k_median(&pos, &weights, k).fuel,
+                    brute_force_k(&pos, &weights, k),
+                    "{:?} weighted {:?}",
+                    pos,
+                    weights
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_weights() {
+        // the heavy crab does not move
+        let meeting = weighted_k_median(&[0, 10, 4], &[1, 5, 1], 1);
+        assert_eq!(meeting.points, vec![10]);
+        assert_eq!(meeting.fuel, 16);
+    }
+
+    #[test]
+    fn test_large_positions() {
+        // the brute force would need 5 * 10^10 steps for this
+        let pos = random_positions(7, 10_000, 5_000_000);
+        assert!(median_linear(&pos).fuel < mean_triangular(&pos).fuel);
+        assert_eq!(align(&pos, &Linear).fuel, median_linear(&pos).fuel);
+        assert_eq!(align(&pos, &Triangular), mean_triangular(&pos));
     }
 }
@@ -1,6 +1,8 @@
 use std::collections::HashMap;
 use std::collections::HashSet;
-use std::fs;
+
+use crate::error::ParseError;
+use crate::Solution;
 
 struct Code {
     digits: Vec<HashSet<char>>,
@@ -16,7 +18,8 @@ impl Code {
         }
     }
 
-    fn new(training_data: &str) -> Code {
+    /// Works out the digits from their ten patterns, `None` if they don't fit
+    fn new(training_data: &str) -> Option<Code> {
         let digit_lens = HashMap::from(Self::DIGITS_LENS);
         let train_digits: Vec<HashSet<char>> = training_data
             .split(" ")
@@ -31,14 +34,14 @@ impl Code {
             match t_digit.len() {
                 6 => six_d.push(t_digit),
                 5 => five_d.push(t_digit),
-                len => digits[*digit_lens.get(&len).unwrap()] = Some(t_digit.clone()),
+                len => digits[*digit_lens.get(&len)?] = Some(t_digit.clone()),
             }
         }
 
         for sd in six_d {
-            if sd.intersection(&digits[1].as_ref().unwrap()).count() == 1 {
+            if sd.intersection(digits[1].as_ref()?).count() == 1 {
                 digits[6] = Some(sd.clone());
-            } else if sd.intersection(&digits[4].as_ref().unwrap()).count() == 4 {
+            } else if sd.intersection(digits[4].as_ref()?).count() == 4 {
                 digits[9] = Some(sd.clone());
             } else {
                 digits[0] = Some(sd.clone());
@@ -46,20 +49,21 @@ impl Code {
         }
 
         for fd in five_d {
-            if fd.intersection(&digits[1].as_ref().unwrap()).count() == 2 {
+            if fd.intersection(digits[1].as_ref()?).count() == 2 {
                 digits[3] = Some(fd.clone());
-            } else if fd.intersection(&digits[4].as_ref().unwrap()).count() == 2 {
+            } else if fd.intersection(digits[4].as_ref()?).count() == 2 {
                 digits[2] = Some(fd.clone());
             } else {
                 digits[5] = Some(fd.clone());
             }
         }
 
-        Code {
-            digits: digits.into_iter().map(|f| f.unwrap()).collect(),
-        }
+        Some(Code {
+            digits: digits.into_iter().collect::<Option<_>>()?,
+        })
     }
 
+    /// The number shown by the output `chars`, `None` if a digit is unknown
     fn decode(&self, chars: &str) -> Option<usize> {
         let digits: Vec<HashSet<char>> = chars
             .split(" ")
@@ -79,56 +83,101 @@ impl Code {
                     }
                 }
             }
+            if !found_match {
+                return None;
+            }
         }
 
         Some(result)
     }
 }
 
-// part one without struct logic, since it will work fine like this
-fn part_one(filename: &str) -> usize {
-    fs::read_to_string(filename)
-        .unwrap()
-        .lines()
-        .map(|x| {
-            let mut iter = x.split(" | ");
-            iter.next();
-            let s = iter.next().unwrap();
-            s.split(" ")
-                .map(|x| x.len())
-                .filter(|len| match len {
-                    4 => true,
-                    3 => true,
-                    2 => true,
-                    7 => true,
-                    _ => false,
-                })
-                .count()
-        })
-        .sum()
-}
+pub struct Day8;
 
-// part one without struct logic, since it will work fine like this
-fn part_two(filename: &str) -> usize {
-    fs::read_to_string(filename)
-        .unwrap()
-        .lines()
-        .map(|x| {
-            let mut iter = x.split(" | ");
-            let code = Code::new(iter.next().unwrap());
-            code.decode(iter.next().unwrap()).unwrap()
-        })
-        .sum()
+impl Day8 {
+    /// Sorted segment counts of the ten digit patterns
+    const PATTERN_LENS: [usize; 10] = [2, 3, 4, 5, 5, 5, 6, 6, 6, 7];
 }
 
-fn main() -> Result<(), Box<dyn std::error::Error + 'static>> {
-    let p1 = part_one("inputs/day8");
-    println!("Answer part one: {}", p1);
+impl Solution for Day8 {
+    const DAY: u8 = 8;
+
+    /// Pairs of (training digits, output digits)
+    type Input = Vec<(String, String)>;
+    type AnswerOne = usize;
+    type AnswerTwo = usize;
+
+    fn parse(input: &str) -> Result<Vec<(String, String)>, ParseError> {
+        input
+            .lines()
+            .map(|x| {
+                let (training, output) = x
+                    .split_once(" | ")
+                    .ok_or_else(|| ParseError::missing(input, x, "' | ' and output digits"))?;
+
+                if training.split(" ").count() != 10 {
+                    return Err(ParseError::invalid(input, training, "10 signal patterns"));
+                }
+                if output.split(" ").count() != 4 {
+                    return Err(ParseError::invalid(input, output, "4 output digits"));
+                }
+                for digit in training.split(" ").chain(output.split(" ")) {
+                    if digit.is_empty() || !digit.chars().all(|c| ('a'..='g').contains(&c)) {
+                        return Err(ParseError::invalid(input, digit, "segments from a to g"));
+                    }
+                    if HashSet::<char>::from_iter(digit.chars()).len() != digit.len() {
+                        return Err(ParseError::invalid(input, digit, "distinct segments"));
+                    }
+                }
+
+                let mut lens: Vec<usize> = training.split(" ").map(str::len).collect();
+                lens.sort_unstable();
+                if lens != Self::PATTERN_LENS {
+                    return Err(ParseError::invalid(
+                        input,
+                        training,
+                        "patterns of 2, 3, 4 and 7 segments, three of 5 and three of 6",
+                    ));
+                }
+
+                let code = Code::new(training).ok_or_else(|| {
+                    ParseError::invalid(input, training, "patterns of the digits 0 to 9")
+                })?;
+                for digit in output.split(" ") {
+                    if code.decode(digit).is_none() {
+                        return Err(ParseError::invalid(
+                            input,
+                            digit,
+                            "one of the signal patterns",
+                        ));
+                    }
+                }
+
+                Ok((training.to_string(), output.to_string()))
+            })
+            .collect()
+    }
 
-    let p2 = part_two("inputs/day8");
-    println!("Answer part two: {}", p2);
+    // part one without struct logic, since it will work fine like this
+    fn part_one(entries: &Vec<(String, String)>) -> usize {
+        entries
+            .iter()
+            .map(|(_, output)| {
+                output
+                    .split(" ")
+                    .map(|x| x.len())
+                    .filter(|len| matches!(len, 4 | 3 | 2 | 7))
+                    .count()
+            })
+            .sum()
+    }
 
-    Ok(())
+    fn part_two(entries: &Vec<(String, String)>) -> usize {
+        entries
+            .iter()
+            .map(|(training, output)| Code::new(training).unwrap().decode(output).unwrap())
+            .sum()
+    }
 }
 
 #[cfg(test)]
@@ -137,13 +186,46 @@ mod tests {
 
     #[test]
     fn test_part_one() {
-        let p1 = part_one("inputs/day8_test");
+        let entries = Day8::parse_file("inputs/day8_test").unwrap();
+        let p1 = Day8::part_one(&entries);
         assert_eq!(p1, 26);
     }
 
     #[test]
     fn test_part_two() {
-        let p2 = part_two("inputs/day8_test");
+        let entries = Day8::parse_file("inputs/day8_test").unwrap();
+        let p2 = Day8::part_two(&entries);
         assert_eq!(p2, 61229);
     }
+
+    #[test]
+    fn test_parse_errors() {
+        let err = Day8::parse("a b c d e f g ab abc abcd | ab ab ab ab").unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "<input>:1:1: expected patterns of 2, 3, 4 and 7 segments, three of 5 and \
+             three of 6, found 'a b c d e f g ab abc abcd'"
+        );
+
+        // right lengths, but two patterns for the six segment digits are the same
+        let same = "ab abc abcd abcde abcdf abcdg abcdef abcdef abcdeg abcdefg | ab ab ab ab";
+        assert!(Day8::parse(same).is_err());
+
+        let entry =
+            "acedgfb cdfbe gcdfa fbcad dab cefabd cdfgeb eafb cagedb ab | cdfeb fcadb cdfeb cdbaf";
+        assert!(Day8::parse(entry).is_ok());
+        let err = Day8::parse(&entry.replace("| cdfeb", "| cdfeg")).unwrap_err();
+        assert!(err
+            .to_string()
+            .contains("expected one of the signal patterns"));
+        let err = Day8::parse(&entry.replace("| cdfeb", "| cdffb")).unwrap_err();
+        assert!(err.to_string().contains("expected distinct segments"));
+
+        let err = Day8::parse(&format!("{} cdfeb", entry)).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "<input>:1:62: expected 4 output digits, found 'cdfeb fcadb cdfeb cdbaf cdfeb'"
+        );
+        assert!(Day8::parse(&entry.replace(" cdbaf", "")).is_err());
+    }
 }
@@ -1,133 +1,67 @@
 use std::collections::HashSet;
-use std::fmt::Display;
-use std::{fs, io};
-
-#[derive(Debug, Clone)]
-struct Field {
-    numbers: Vec<u8>,
-    pub rows: usize,
-    pub cols: usize,
-}
-
-impl Field {
-    fn from_str(s: &str) -> Field {
-        let numbers = s
-            .replace("\n", "")
-            .chars()
-            .map(|c| c.to_digit(10).unwrap() as u8)
-            .collect();
-        let rows = s.lines().count();
-        let cols = s.lines().next().unwrap().len();
-
-        Field {
-            numbers,
-            rows,
-            cols,
-        }
-    }
 
-    fn get(&self, x: isize, y: isize) -> Option<u8> {
-        if x < self.cols as isize && 0 <= x && y < self.rows as isize && 0 <= y {
-            Some(self.numbers[self.cols * y as usize + x as usize])
-        } else {
-            None
-        }
-    }
+use crate::error::ParseError;
+use crate::grid::Grid;
+use crate::Solution;
 
-    fn get_low_points(&self) -> Vec<(isize, isize)> {
-        let mut low_points = Vec::new();
-
-        for x in 0..self.cols as isize {
-            'ns: for y in 0..self.rows as isize {
-                let number = self.get(x, y).unwrap();
-                for (dx, dy) in [(0isize, 1isize), (0, -1), (1, 0), (-1, 0)] {
-                    match self.get(x + dx, y + dy) {
-                        Some(n) => {
-                            if n <= number {
-                                continue 'ns;
-                            }
-                        }
-                        None => (),
-                    }
-                }
-                low_points.push((x, y));
-            }
-        }
-        low_points
-    }
+fn get_low_points(field: &Grid<u8>) -> Vec<(usize, usize)> {
+    field
+        .positions()
+        .filter(|&pos| field.neighbours4(pos).all(|n| field[n] > field[pos]))
+        .collect()
 }
 
-impl Display for Field {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        let s: String = self
-            .numbers
-            .chunks(self.cols)
-            .map(|x| x.iter().map(|x| format!("{}", x)).collect::<String>() + "\n")
-            .collect();
+pub struct Day9;
 
-        write!(f, "{}", s)
-    }
-}
+impl Solution for Day9 {
+    const DAY: u8 = 9;
 
-fn parse(filename: &str) -> io::Result<Field> {
-    let file = fs::read_to_string(filename)?;
+    /// Height map
+    type Input = Grid<u8>;
+    type AnswerOne = usize;
+    type AnswerTwo = usize;
 
-    Ok(Field::from_str(&file))
-}
+    fn parse(input: &str) -> Result<Grid<u8>, ParseError> {
+        Grid::from_digits(input)
+    }
 
-fn part_one(field: &Field) -> usize {
-    field
-        .get_low_points()
-        .iter()
-        .map(|&f| field.get(f.0, f.1).unwrap() as usize + 1)
-        .sum()
-}
+    fn part_one(field: &Grid<u8>) -> usize {
+        get_low_points(field)
+            .iter()
+            .map(|&f| field[f] as usize + 1)
+            .sum()
+    }
 
-fn part_two(field: &Field) -> usize {
-    let mut clusters: Vec<usize> = field
-        .get_low_points()
-        .iter()
-        .map(|&f| {
-            let mut visited: HashSet<(isize, isize)> = HashSet::new();
-            let mut que: Vec<(isize, isize)> = vec![f];
-            let mut size = 0;
-
-            loop {
-                let pos = match que.pop() {
-                    Some(p) if visited.contains(&p) => continue,
-                    Some(p) => p,
-                    None => break,
-                };
-
-                visited.insert(pos);
-                let val = match field.get(pos.0, pos.1) {
-                    Some(x) => x,
-                    None => continue,
-                };
-
-                if val < 9 {
-                    size += 1;
-                    for (dx, dy) in [(0isize, 1isize), (0, -1), (1, 0), (-1, 0)] {
-                        que.push((pos.0 + dx, pos.1 + dy));
+    fn part_two(field: &Grid<u8>) -> usize {
+        let mut clusters: Vec<usize> = get_low_points(field)
+            .iter()
+            .map(|&f| {
+                let mut visited: HashSet<(usize, usize)> = HashSet::new();
+                let mut que: Vec<(usize, usize)> = vec![f];
+                let mut size = 0;
+
+                loop {
+                    let pos = match que.pop() {
+                        Some(p) if visited.contains(&p) => continue,
+                        Some(p) => p,
+                        None => break,
+                    };
+
+                    visited.insert(pos);
+
+                    if field[pos] < 9 {
+                        size += 1;
+                        que.extend(field.neighbours4(pos));
                     }
                 }
-            }
-            size
-        })
-        .collect();
-
-    clusters.sort_unstable();
-    let size = clusters.len();
-    clusters[size - 1] * clusters[size - 2] * clusters[size - 3]
-}
-
-fn main() -> Result<(), Box<dyn std::error::Error + 'static>> {
-    let field = parse("inputs/day9").unwrap();
-
-    println!("Answer part one: {}", part_one(&field));
-    println!("Answer part two: {}", part_two(&field));
+                size
+            })
+            .collect();
 
-    Ok(())
+        clusters.sort_unstable();
+        let size = clusters.len();
+        clusters[size - 1] * clusters[size - 2] * clusters[size - 3]
+    }
 }
 
 #[cfg(test)]
@@ -136,24 +70,24 @@ mod tests {
 
     #[test]
     fn test_input() {
-        let field = parse("inputs/day9_test").unwrap();
+        let field = Day9::parse_file("inputs/day9_test").unwrap();
         println!("{}", field);
-        assert_eq!(field.cols, 10);
-        assert_eq!(field.rows, 5);
-        assert_eq!(field.numbers[0], 2);
+        assert_eq!(field.width(), 10);
+        assert_eq!(field.height(), 5);
+        assert_eq!(field[(0, 0)], 2);
     }
 
     #[test]
     fn test_part_one() {
-        let field = parse("inputs/day9_test").unwrap();
+        let field = Day9::parse_file("inputs/day9_test").unwrap();
         println!("{}", field);
-        assert_eq!(part_one(&field), 15);
+        assert_eq!(Day9::part_one(&field), 15);
     }
 
     #[test]
     fn test_part_two() {
-        let field = parse("inputs/day9_test").unwrap();
+        let field = Day9::parse_file("inputs/day9_test").unwrap();
         println!("{}", field);
-        assert_eq!(part_two(&field), 1134);
+        assert_eq!(Day9::part_two(&field), 1134);
     }
 }
@@ -0,0 +1,155 @@
+use std::error::Error;
+use std::fmt::{self, Display};
+use std::str::FromStr;
+
+/// Position of a token in a puzzle input
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Location {
+    pub file: Option<String>,
+    /// Line number, starting at 1
+    pub line: usize,
+    /// Column in characters, starting at 1
+    pub column: usize,
+}
+
+/// Why a puzzle input could not be parsed
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ParseError {
+    /// `token` is not what was `expected`
+    Invalid {
+        location: Location,
+        token: String,
+        expected: String,
+    },
+    /// The line or input ended where `expected` should have been
+    Missing {
+        location: Location,
+        expected: String,
+    },
+}
+
+impl Location {
+    /// Location of the byte `offset` in `input`
+    fn new(input: &str, offset: usize) -> Location {
+        let before = &input[..offset];
+        let line_start = before.rfind('\n').map_or(0, |i| i + 1);
+
+        Location {
+            file: None,
+            line: before.matches('\n').count() + 1,
+            column: before[line_start..].chars().count() + 1,
+        }
+    }
+
+    /// Location of `token`, which has to be a slice of `input`
+    fn of(input: &str, token: &str) -> Location {
+        let offset = (token.as_ptr() as usize)
+            .checked_sub(input.as_ptr() as usize)
+            .filter(|&o| o <= input.len())
+            .expect("token is not a slice of the input");
+
+        Location::new(input, offset)
+    }
+}
+
+impl ParseError {
+    /// `token` (a slice of `input`) is not what was `expected`
+    pub fn invalid(input: &str, token: &str, expected: impl Into<String>) -> ParseError {
+        ParseError::Invalid {
+            location: Location::of(input, token),
+            token: token.to_string(),
+            expected: expected.into(),
+        }
+    }
+
+    /// `expected` is missing right after `before`, a slice of `input`
+    pub fn missing(input: &str, before: &str, expected: impl Into<String>) -> ParseError {
+        let end = &before[before.len()..];
+        ParseError::Missing {
+            location: Location::of(input, end),
+            expected: expected.into(),
+        }
+    }
+
+    pub fn location(&self) -> &Location {
+        match self {
+            ParseError::Invalid { location, .. } => location,
+            ParseError::Missing { location, .. } => location,
+        }
+    }
+
+    /// Records the name of the file the error occurred in
+    pub fn in_file(mut self, file: &str) -> ParseError {
+        match &mut self {
+            ParseError::Invalid { location, .. } => location.file = Some(file.to_string()),
+            ParseError::Missing { location, .. } => location.file = Some(file.to_string()),
+        }
+        self
+    }
+}
+
+impl Display for Location {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let file = self.file.as_deref().unwrap_or("<input>");
+        write!(f, "{}:{}:{}", file, self.line, self.column)
+    }
+}
+
+impl Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ParseError::Invalid {
+                location,
+                token,
+                expected,
+            } => write!(f, "{}: expected {}, found '{}'", location, expected, token),
+            ParseError::Missing { location, expected } => {
+                write!(f, "{}: expected {}, found nothing", location, expected)
+            }
+        }
+    }
+}
+
+impl Error for ParseError {}
+
+/// Parses `token` (a slice of `input`) with [`FromStr`]
+pub fn parse_token<T: FromStr>(input: &str, token: &str, expected: &str) -> Result<T, ParseError> {
+    token
+        .parse()
+        .map_err(|_| ParseError::invalid(input, token, expected))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_location() {
+        let input = "12\n3x4\n";
+        let err = parse_token::<u32>(input, &input[3..6], "a number").unwrap_err();
+        assert_eq!(
+            err.location(),
+            &Location {
+                file: None,
+                line: 2,
+                column: 1
+            }
+        );
+        assert_eq!(
+            err.in_file("inputs/day1").to_string(),
+            "inputs/day1:2:1: expected a number, found '3x4'"
+        );
+    }
+
+    #[test]
+    fn test_missing() {
+        let input = "forward 5\nup";
+        let err = ParseError::missing(input, &input[10..], "a number");
+        assert_eq!(err.location().line, 2);
+        assert_eq!(err.location().column, 3);
+        assert_eq!(
+            err.to_string(),
+            "<input>:2:3: expected a number, found nothing"
+        );
+    }
+}
@@ -0,0 +1,300 @@
+use std::fmt::{self, Display};
+use std::ops::{Index, IndexMut};
+
+use crate::error::ParseError;
+
+/// Offsets of the 4 direct neighbours
+const NEIGHBOURS_4: [(isize, isize); 4] = [(0, 1), (0, -1), (1, 0), (-1, 0)];
+
+/// Offsets of the 8 neighbours, including diagonals
+const NEIGHBOURS_8: [(isize, isize); 8] = [
+    (0, 1),
+    (0, -1),
+    (1, 0),
+    (-1, 0),
+    (1, 1),
+    (1, -1),
+    (-1, -1),
+    (-1, 1),
+];
+
+/// A rectangular grid, indexed by `(x, y)` with x being the column
+///
+/// Cells are stored row-major, so `index = width * y + x`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Grid<T> {
+    cells: Vec<T>,
+    width: usize,
+    height: usize,
+}
+
+impl<T: Clone> Grid<T> {
+    /// A `width` x `height` grid with every cell set to `value`
+    pub fn new(width: usize, height: usize, value: T) -> Grid<T> {
+        Grid {
+            cells: vec![value; width * height],
+            width,
+            height,
+        }
+    }
+}
+
+impl<T> Grid<T> {
+    /// Wraps row-major `cells`, which have to hold `width * height` values
+    pub fn from_vec(width: usize, height: usize, cells: Vec<T>) -> Grid<T> {
+        assert_eq!(cells.len(), width * height, "cells don't fit the size");
+        Grid {
+            cells,
+            width,
+            height,
+        }
+    }
+
+    /// Parses a map with one cell per character, `convert` rejects unknown characters
+    pub fn from_chars(
+        input: &str,
+        convert: impl Fn(char) -> Option<T>,
+        expected: &str,
+    ) -> Result<Grid<T>, ParseError> {
+        let width = match input.lines().next() {
+            Some(line) if line.is_empty() => return Err(ParseError::missing(input, line, "a row")),
+            Some(line) => line.chars().count(),
+            None => return Err(ParseError::missing(input, input, "a row")),
+        };
+
+        let mut cells = Vec::new();
+        for line in input.lines() {
+            if line.chars().count() != width {
+                return Err(ParseError::invalid(
+                    input,
+                    line,
+                    format!("a row of {} cells", width),
+                ));
+            }
+            for (i, c) in line.char_indices() {
+                match convert(c) {
+                    Some(cell) => cells.push(cell),
+                    None => {
+                        return Err(ParseError::invalid(
+                            input,
+                            &line[i..i + c.len_utf8()],
+                            expected,
+                        ))
+                    }
+                }
+            }
+        }
+
+        let height = cells.len() / width;
+        Ok(Grid::from_vec(width, height, cells))
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    /// Number of cells
+    pub fn len(&self) -> usize {
+        self.cells.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.cells.is_empty()
+    }
+
+    /// Index into the row-major cells, `None` if `pos` is outside
+    pub fn get_index(&self, (x, y): (usize, usize)) -> Option<usize> {
+        match x < self.width && y < self.height {
+            true => Some(self.width * y + x),
+            false => None,
+        }
+    }
+
+    /// Inverse of [`Grid::get_index`]
+    pub fn get_coord(&self, index: usize) -> (usize, usize) {
+        (index % self.width, index / self.width)
+    }
+
+    pub fn get(&self, pos: (usize, usize)) -> Option<&T> {
+        self.get_index(pos).map(|i| &self.cells[i])
+    }
+
+    pub fn get_mut(&mut self, pos: (usize, usize)) -> Option<&mut T> {
+        self.get_index(pos).map(move |i| &mut self.cells[i])
+    }
+
+    /// Positions at `offsets` from `pos` that lie inside the grid
+    fn offset_positions(
+        &self,
+        (x, y): (usize, usize),
+        offsets: &'static [(isize, isize)],
+    ) -> impl Iterator<Item = (usize, usize)> {
+        let (width, height) = (self.width, self.height);
+        offsets.iter().filter_map(move |&(dx, dy)| {
+            let nx = x.checked_add_signed(dx)?;
+            let ny = y.checked_add_signed(dy)?;
+            match nx < width && ny < height {
+                true => Some((nx, ny)),
+                false => None,
+            }
+        })
+    }
+
+    /// The up to 4 horizontal and vertical neighbours of `pos`
+    pub fn neighbours4(&self, pos: (usize, usize)) -> impl Iterator<Item = (usize, usize)> {
+        self.offset_positions(pos, &NEIGHBOURS_4)
+    }
+
+    /// The up to 8 neighbours of `pos`, including diagonals
+    pub fn neighbours8(&self, pos: (usize, usize)) -> impl Iterator<Item = (usize, usize)> {
+        self.offset_positions(pos, &NEIGHBOURS_8)
+    }
+
+    /// All cells in row-major order
+    pub fn iter(&self) -> std::slice::Iter<'_, T> {
+        self.cells.iter()
+    }
+
+    pub fn iter_mut(&mut self) -> std::slice::IterMut<'_, T> {
+        self.cells.iter_mut()
+    }
+
+    /// All positions in row-major order
+    pub fn positions(&self) -> impl Iterator<Item = (usize, usize)> {
+        let width = self.width;
+        (0..self.height).flat_map(move |y| (0..width).map(move |x| (x, y)))
+    }
+
+    pub fn row(&self, y: usize) -> &[T] {
+        &self.cells[self.width * y..self.width * (y + 1)]
+    }
+
+    pub fn rows(&self) -> impl Iterator<Item = &[T]> {
+        // chunks panics on 0, an empty grid has no rows anyway
+        self.cells.chunks(self.width.max(1))
+    }
+
+    pub fn column(&self, x: usize) -> impl Iterator<Item = &T> {
+        assert!(x < self.width, "column {} out of range", x);
+        self.cells.iter().skip(x).step_by(self.width)
+    }
+
+    pub fn columns(&self) -> impl Iterator<Item = impl Iterator<Item = &T>> {
+        (0..self.width).map(move |x| self.column(x))
+    }
+
+    /// A grid of the same size with `f` applied to every cell
+    pub fn map<U>(&self, f: impl FnMut(&T) -> U) -> Grid<U> {
+        Grid::from_vec(self.width, self.height, self.cells.iter().map(f).collect())
+    }
+}
+
+impl Grid<u8> {
+    /// Parses a map of single digits, like '2199943210'
+    pub fn from_digits(input: &str) -> Result<Grid<u8>, ParseError> {
+        Grid::from_chars(input, |c| c.to_digit(10).map(|d| d as u8), "a digit")
+    }
+}
+
+impl<T> Index<(usize, usize)> for Grid<T> {
+    type Output = T;
+
+    fn index(&self, pos: (usize, usize)) -> &Self::Output {
+        match self.get(pos) {
+            Some(cell) => cell,
+            None => panic!(
+                "{:?} is outside of the {}x{} grid",
+                pos, self.width, self.height
+            ),
+        }
+    }
+}
+
+impl<T> IndexMut<(usize, usize)> for Grid<T> {
+    fn index_mut(&mut self, pos: (usize, usize)) -> &mut Self::Output {
+        let (width, height) = (self.width, self.height);
+        match self.get_mut(pos) {
+            Some(cell) => cell,
+            None => panic!("{:?} is outside of the {}x{} grid", pos, width, height),
+        }
+    }
+}
+
+impl<T: Display> Display for Grid<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for row in self.rows() {
+            for cell in row {
+                write!(f, "{}", cell)?;
+            }
+            writeln!(f)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MAP: &str = "123\n456\n";
+
+    #[test]
+    fn test_from_digits() {
+        let grid = Grid::from_digits(MAP).unwrap();
+        assert_eq!(grid.width(), 3);
+        assert_eq!(grid.height(), 2);
+        assert_eq!(grid[(2, 0)], 3);
+        assert_eq!(grid[(0, 1)], 4);
+        assert_eq!(grid.get((3, 0)), None);
+        assert_eq!(grid.to_string(), MAP);
+
+        let err = Grid::from_digits("12\n3x\n").unwrap_err();
+        assert_eq!(err.to_string(), "<input>:2:2: expected a digit, found 'x'");
+        assert!(Grid::from_digits("12\n3\n").is_err());
+
+        let err = Grid::from_digits("\n").unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "<input>:1:1: expected a row, found nothing"
+        );
+        assert!(Grid::from_digits("\n\n").is_err());
+        assert!(Grid::from_digits("").is_err());
+    }
+
+    #[test]
+    fn test_index() {
+        let grid = Grid::from_digits(MAP).unwrap();
+        assert_eq!(grid.get_index((1, 1)), Some(4));
+        assert_eq!(grid.get_coord(4), (1, 1));
+        assert_eq!(grid.get_index((0, 2)), None);
+    }
+
+    #[test]
+    fn test_neighbours() {
+        let grid = Grid::new(3, 3, 0);
+        let mut n4: Vec<_> = grid.neighbours4((0, 0)).collect();
+        n4.sort_unstable();
+        assert_eq!(n4, vec![(0, 1), (1, 0)]);
+
+        assert_eq!(grid.neighbours4((1, 1)).count(), 4);
+        assert_eq!(grid.neighbours8((1, 1)).count(), 8);
+        assert_eq!(grid.neighbours8((2, 2)).count(), 3);
+    }
+
+    #[test]
+    fn test_rows_and_columns() {
+        let grid = Grid::from_digits(MAP).unwrap();
+        assert_eq!(grid.row(1), &[4, 5, 6]);
+        assert_eq!(grid.rows().count(), 2);
+        assert_eq!(grid.column(1).copied().collect::<Vec<_>>(), vec![2, 5]);
+        assert_eq!(
+            grid.columns().map(|c| c.sum::<u8>()).collect::<Vec<_>>(),
+            vec![5, 7, 9]
+        );
+        assert_eq!(grid.positions().nth(4), Some((1, 1)));
+    }
+}
@@ -0,0 +1,81 @@
+use std::fs;
+use std::io::{self, Read};
+
+/// Where the puzzle input of a day is read from
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Source {
+    /// `inputs/dayN` relative to the working directory
+    Default,
+    File(String),
+    Stdin,
+}
+
+impl Source {
+    /// Interprets a command line argument, `-` meaning stdin
+    pub fn from_arg(arg: &str) -> Source {
+        match arg {
+            "-" => Source::Stdin,
+            path => Source::File(path.to_string()),
+        }
+    }
+
+    /// Name of the input in messages
+    pub fn name(&self, day: u8) -> String {
+        match self {
+            Source::Default => default_path(day),
+            Source::File(path) => path.clone(),
+            Source::Stdin => String::from("<stdin>"),
+        }
+    }
+
+    /// Reads the whole input of `day`
+    pub fn read(&self, day: u8) -> io::Result<String> {
+        match self {
+            Source::Default => read_file(&default_path(day)).map_err(|e| {
+                io::Error::new(
+                    e.kind(),
+                    format!("{} (use --input <path> or - for stdin)", e),
+                )
+            }),
+            Source::File(path) => read_file(path),
+            Source::Stdin => {
+                let mut input = String::new();
+                io::stdin().read_to_string(&mut input)?;
+                Ok(input)
+            }
+        }
+    }
+}
+
+pub fn default_path(day: u8) -> String {
+    format!("inputs/day{}", day)
+}
+
+/// Like `fs::read_to_string`, but the error names the file
+fn read_file(path: &str) -> io::Result<String> {
+    fs::read_to_string(path)
+        .map_err(|e| io::Error::new(e.kind(), format!("Could not read '{}': {}", path, e)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_from_arg() {
+        assert_eq!(Source::from_arg("-"), Source::Stdin);
+        assert_eq!(
+            Source::from_arg("inputs/day1"),
+            Source::File(String::from("inputs/day1"))
+        );
+    }
+
+    #[test]
+    fn test_missing_file() {
+        let err = Source::File(String::from("inputs/does_not_exist"))
+            .read(1)
+            .unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::NotFound);
+        assert!(err.to_string().contains("inputs/does_not_exist"));
+    }
+}
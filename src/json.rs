@@ -0,0 +1,70 @@
+//! Just enough JSON to print results
+
+/// `s` as a JSON string, with quotes
+pub fn string(s: &str) -> String {
+    let mut json = String::with_capacity(s.len() + 2);
+    json.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => json.push_str("\\\""),
+            '\\' => json.push_str("\\\\"),
+            '\n' => json.push_str("\\n"),
+            '\r' => json.push_str("\\r"),
+            '\t' => json.push_str("\\t"),
+            c if c.is_control() => json.push_str(&format!("\\u{:04x}", c as u32)),
+            c => json.push(c),
+        }
+    }
+    json.push('"');
+    json
+}
+
+/// `s` as a JSON number if it is an integer, as a string otherwise
+pub fn value(s: &str) -> String {
+    let digits = s.strip_prefix('-').unwrap_or(s);
+    let is_integer = !digits.is_empty()
+        && digits.bytes().all(|b| b.is_ascii_digit())
+        && (digits == "0" || !digits.starts_with('0'));
+
+    match is_integer {
+        true => s.to_string(),
+        false => string(s),
+    }
+}
+
+/// An object of already encoded `fields`
+pub fn object(fields: &[(&str, String)]) -> String {
+    let fields: Vec<String> = fields
+        .iter()
+        .map(|(key, value)| format!("{}:{}", string(key), value))
+        .collect();
+    format!("{{{}}}", fields.join(","))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_string() {
+        assert_eq!(string("abc"), "\"abc\"");
+        assert_eq!(string("# \"a\"\\\n"), "\"# \\\"a\\\"\\\\\\n\"");
+        assert_eq!(string("\u{1}"), "\"\\u0001\"");
+    }
+
+    #[test]
+    fn test_value() {
+        assert_eq!(value("1624"), "1624");
+        assert_eq!(value("-3"), "-3");
+        assert_eq!(value("0"), "0");
+        assert_eq!(value("007"), "\"007\"");
+        assert_eq!(value("LKREBPRK"), "\"LKREBPRK\"");
+        assert_eq!(value("-"), "\"-\"");
+    }
+
+    #[test]
+    fn test_object() {
+        let fields = [("day", String::from("1")), ("answer", string("a"))];
+        assert_eq!(object(&fields), "{\"day\":1,\"answer\":\"a\"}");
+    }
+}
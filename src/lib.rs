@@ -0,0 +1,158 @@
+//! (Some of the) Advent of Code 2021 in Rust.
+//!
+//! Every day lives in its own module and implements [`Solution`],
+//! which the `aoc` binary dispatches to.
+
+use std::error::Error;
+use std::time::{Duration, Instant};
+
+use error::ParseError;
+
+pub mod answers;
+pub mod bench;
+pub mod bigint;
+pub mod error;
+pub mod grid;
+pub mod input;
+pub mod json;
+pub mod ocr;
+mod solution;
+pub use solution::{Outcome, Solution};
+
+pub mod day1;
+pub mod day10;
+pub mod day11;
+pub mod day12;
+pub mod day13;
+pub mod day14;
+pub mod day15;
+pub mod day2;
+pub mod day3;
+pub mod day4;
+pub mod day5;
+pub mod day6;
+pub mod day7;
+pub mod day8;
+pub mod day9;
+
+/// Number of days that have a solution
+pub const DAYS: u8 = 15;
+
+/// Calls the generic function `f` with the [`Solution`] of `day`
+///
+/// Evaluates to `None` for days without a solution.
+macro_rules! dispatch {
+    ($day:expr, $f:ident($($arg:expr),*)) => {
+        match $day {
+            1 => Some($f::<day1::Day1>($($arg),*)),
+            2 => Some($f::<day2::Day2>($($arg),*)),
+            3 => Some($f::<day3::Day3>($($arg),*)),
+            4 => Some($f::<day4::Day4>($($arg),*)),
+            5 => Some($f::<day5::Day5>($($arg),*)),
+            6 => Some($f::<day6::Day6>($($arg),*)),
+            7 => Some($f::<day7::Day7>($($arg),*)),
+            8 => Some($f::<day8::Day8>($($arg),*)),
+            9 => Some($f::<day9::Day9>($($arg),*)),
+            10 => Some($f::<day10::Day10>($($arg),*)),
+            11 => Some($f::<day11::Day11>($($arg),*)),
+            12 => Some($f::<day12::Day12>($($arg),*)),
+            13 => Some($f::<day13::Day13>($($arg),*)),
+            14 => Some($f::<day14::Day14>($($arg),*)),
+            15 => Some($f::<day15::Day15>($($arg),*)),
+            _ => None,
+        }
+    };
+}
+
+fn unknown_day(day: u8) -> Box<dyn Error + 'static> {
+    format!("Unknown day {}, expected 1 to {}", day, DAYS).into()
+}
+
+/// The answer to a part of a day, with how long it took
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Answer {
+    pub day: u8,
+    pub part: u8,
+    /// The error if the part has no answer for the input
+    pub value: Result<String, String>,
+    /// Time spent parsing the input, which is shared by the parts of a day
+    pub parse_time: Duration,
+    pub solve_time: Duration,
+}
+
+impl Answer {
+    /// One JSON object, with the durations in nanoseconds
+    ///
+    /// A part without an answer has an "error" field instead of "answer".
+    pub fn to_json(&self) -> String {
+        let value = match &self.value {
+            Ok(answer) => ("answer", json::value(answer)),
+            Err(e) => ("error", json::string(e)),
+        };
+        json::object(&[
+            ("day", self.day.to_string()),
+            ("part", self.part.to_string()),
+            value,
+            ("parse_ns", self.parse_time.as_nanos().to_string()),
+            ("solve_ns", self.solve_time.as_nanos().to_string()),
+        ])
+    }
+}
+
+/// Parses `input` once and solves each of `parts` of it
+fn run_solution<S: Solution>(input: &str, parts: &[u8]) -> Result<Vec<Answer>, ParseError> {
+    let start = Instant::now();
+    let input = S::parse(input)?;
+    let parse_time = start.elapsed();
+
+    Ok(parts
+        .iter()
+        .map(|&part| {
+            let start = Instant::now();
+            let value = match part {
+                1 => S::part_one(&input).into_answer(),
+                _ => S::part_two(&input).into_answer(),
+            };
+            Answer {
+                day: S::DAY,
+                part,
+                value,
+                parse_time,
+                solve_time: start.elapsed(),
+            }
+        })
+        .collect())
+}
+
+/// Solves `parts` (1 or 2) of `day` for the puzzle `input` and returns the answers
+///
+/// `name` is the file the input was read from, to point parse errors at it.
+pub fn run(
+    day: u8,
+    parts: &[u8],
+    input: &str,
+    name: &str,
+) -> Result<Vec<Answer>, Box<dyn Error + 'static>> {
+    if let Some(part) = parts.iter().find(|&&p| p != 1 && p != 2) {
+        return Err(format!("Unknown part {}, expected 1 or 2", part).into());
+    }
+
+    let answers = dispatch!(day, run_solution(input, parts)).ok_or_else(|| unknown_day(day))?;
+
+    Ok(answers.map_err(|e| e.in_file(name))?)
+}
+
+/// Times parsing and both parts of `day`, each `reps` times
+///
+/// `name` is the file the input was read from, to point parse errors at it.
+pub fn bench(
+    day: u8,
+    input: &str,
+    name: &str,
+    reps: usize,
+) -> Result<Vec<bench::Timing>, Box<dyn Error + 'static>> {
+    use bench::bench_solution;
+
+    let timings = dispatch!(day, bench_solution(input, reps)).ok_or_else(|| unknown_day(day))?;
+    Ok(timings.map_err(|e| e.in_file(name))?)
+}
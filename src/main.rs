@@ -0,0 +1,529 @@
+use std::env;
+use std::error::Error;
+use std::fs;
+use std::process;
+
+use rustvent2021::answers::{self, Answers, Verdict};
+use rustvent2021::bench::to_csv;
+use rustvent2021::day1::{self, Day1, Profile};
+use rustvent2021::day13::Day13;
+use rustvent2021::day15::{self, Day15, Tiled};
+use rustvent2021::day6::{self, Day6, Population};
+use rustvent2021::input::Source;
+use rustvent2021::{bench, run, Solution, DAYS};
+
+const USAGE: &str = "Usage: aoc <day> [part] [--input <path|->] [--format text|json]
+       aoc all [--format text|json]
+       aoc search [--tiles <n>] [--path] [--input <path|->]
+       aoc bench [day] [--reps <n>] [--output <path>] [--input <path|->]
+       aoc verify [--answers <path>]
+       aoc frames [--format text|pbm] [--output <dir>] [--input <path|->]
+       aoc series [--days <n>] [--buckets] [--output <path>] [--input <path|->]
+       aoc profile [--width <n>] [--input <path|->]
+
+Without --input, day N reads inputs/dayN. Use --input - to read stdin.
+--format json prints one JSON object per line and answer, with the
+parse and solve durations in nanoseconds.
+search compares the day 15 path finding algorithms on the cave,
+tiled n times in each direction (default 1), with --path it also
+prints the cave with the risks on the lowest risk path in brackets.
+bench times parsing and both parts of one or every day n times
+(default 10) and writes a CSV summary to the output (default bench.csv).
+verify solves every day and compares the results to the answers file
+(default inputs/answers).
+frames writes the day 13 paper before and after every fold as a text
+or PBM file into the output directory (default frames).
+series prints the day 6 population on every day up to n (default 256)
+as CSV, with --buckets also the number of fish per timer value.
+profile prints statistics of the day 1 depths and a sparkline of them,
+n characters wide (default 72).";
+
+const DEFAULT_REPS: usize = 10;
+const DEFAULT_BENCH_OUTPUT: &str = "bench.csv";
+const DEFAULT_FRAMES_OUTPUT: &str = "frames";
+const DEFAULT_SERIES_DAYS: u32 = 256;
+const DEFAULT_SPARKLINE_WIDTH: usize = 72;
+
+/// What the binary was asked to do
+enum Mode {
+    /// Solve the days, with their parts
+    Solve(Vec<(u8, Vec<u8>)>),
+    /// Compare the day 15 search algorithms on the tiled cave, with `path` also the best path
+    Search { tiles: usize, path: bool },
+    /// Time the days and write a summary to `output`
+    Bench {
+        days: Vec<u8>,
+        reps: usize,
+        output: String,
+    },
+    /// Solve every day and compare with the answers file
+    Verify { answers: String },
+    /// Write every stage of folding the day 13 paper into `output`
+    Frames { output: String },
+    /// Print or write the day 6 population of every day as CSV
+    Series {
+        days: u32,
+        buckets: bool,
+        output: Option<String>,
+    },
+    /// Print statistics of the day 1 depths
+    Profile { width: usize },
+}
+
+/// How answers or frames are printed
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Format {
+    Text,
+    /// One JSON object per line and answer
+    Json,
+    /// Binary PBM images, for frames
+    Pbm,
+}
+
+/// The parsed command line
+struct Args {
+    mode: Mode,
+    format: Format,
+    source: Source,
+}
+
+fn parse_number(arg: &str, what: &str) -> Result<u8, String> {
+    arg.parse()
+        .map_err(|_| format!("Expected a {} number, got '{}'", what, arg))
+}
+
+fn parse_count(arg: Option<&String>, option: &str) -> Result<usize, String> {
+    match arg.map(|n| n.parse()) {
+        Some(Ok(n)) if n > 0 => Ok(n),
+        _ => Err(format!("{} needs a positive number", option)),
+    }
+}
+
+/// Every day, which all read their default input
+fn all_days(source: &Source) -> Result<Vec<u8>, String> {
+    if *source != Source::Default {
+        return Err(String::from("--input can only be used with a single day"));
+    }
+    Ok((1..=DAYS).collect())
+}
+
+fn parse_args(args: &[String]) -> Result<Args, String> {
+    let mut positional = Vec::new();
+    let mut source = Source::Default;
+    let mut tiles = None;
+    let mut path = false;
+    let mut reps = None;
+    let mut output = None;
+    let mut answers = None;
+    let mut format = Format::Text;
+    let mut days = None;
+    let mut buckets = false;
+    let mut width = None;
+
+    let mut iter = args.iter();
+    while let Some(arg) = iter.next() {
+        match arg.as_str() {
+            "--input" | "-i" => match iter.next() {
+                Some(path) => source = Source::from_arg(path),
+                None => return Err(String::from("--input needs a path or -")),
+            },
+            "--tiles" => tiles = Some(parse_count(iter.next(), "--tiles")?),
+            "--path" => path = true,
+            "--reps" => reps = Some(parse_count(iter.next(), "--reps")?),
+            "--days" => match iter.next().map(|n| n.parse()) {
+                Some(Ok(n)) => days = Some(n),
+                _ => return Err(String::from("--days needs a number")),
+            },
+            "--buckets" => buckets = true,
+            "--width" => width = Some(parse_count(iter.next(), "--width")?),
+            "--output" | "-o" => match iter.next() {
+                Some(path) => output = Some(path.clone()),
+                None => return Err(String::from("--output needs a path")),
+            },
+            "--format" | "-f" => match iter.next().map(String::as_str) {
+                Some("text") => format = Format::Text,
+                Some("json") => format = Format::Json,
+                Some("pbm") => format = Format::Pbm,
+                _ => return Err(String::from("--format needs text, json or pbm")),
+            },
+            "--answers" => match iter.next() {
+                Some(path) => answers = Some(path.clone()),
+                None => return Err(String::from("--answers needs a path")),
+            },
+            "--help" | "-h" => return Err(USAGE.to_string()),
+            _ => positional.push(arg.as_str()),
+        }
+    }
+
+    let command = positional.first().copied();
+    if (tiles.is_some() || path) && command != Some("search") {
+        return Err(String::from(
+            "--tiles and --path can only be used with search",
+        ));
+    }
+    if reps.is_some() && command != Some("bench") {
+        return Err(String::from("--reps can only be used with bench"));
+    }
+    if output.is_some() && !matches!(command, Some("bench" | "frames" | "series")) {
+        return Err(String::from(
+            "--output can only be used with bench, frames or series",
+        ));
+    }
+    if answers.is_some() && command != Some("verify") {
+        return Err(String::from("--answers can only be used with verify"));
+    }
+    if width.is_some() && command != Some("profile") {
+        return Err(String::from("--width can only be used with profile"));
+    }
+    if (days.is_some() || buckets) && command != Some("series") {
+        return Err(String::from(
+            "--days and --buckets can only be used with series",
+        ));
+    }
+
+    let mode = match positional[..] {
+        ["search"] => Mode::Search {
+            tiles: tiles.unwrap_or(1),
+            path,
+        },
+        ["bench", ref days @ ..] if days.len() <= 1 => Mode::Bench {
+            days: match days {
+                [day] => vec![parse_number(day, "day")?],
+                _ => all_days(&source)?,
+            },
+            reps: reps.unwrap_or(DEFAULT_REPS),
+            output: output.unwrap_or_else(|| DEFAULT_BENCH_OUTPUT.to_string()),
+        },
+        ["verify"] => {
+            all_days(&source)?;
+            Mode::Verify {
+                answers: answers.unwrap_or_else(|| answers::DEFAULT_PATH.to_string()),
+            }
+        }
+        ["frames"] => Mode::Frames {
+            output: output.unwrap_or_else(|| DEFAULT_FRAMES_OUTPUT.to_string()),
+        },
+        ["series"] => Mode::Series {
+            days: days.unwrap_or(DEFAULT_SERIES_DAYS),
+            buckets,
+            output,
+        },
+        ["profile"] => Mode::Profile {
+            width: width.unwrap_or(DEFAULT_SPARKLINE_WIDTH),
+        },
+        ["all"] => Mode::Solve(
+            all_days(&source)?
+                .into_iter()
+                .map(|d| (d, vec![1, 2]))
+                .collect(),
+        ),
+        [day] => Mode::Solve(vec![(parse_number(day, "day")?, vec![1, 2])]),
+        [day, part] => Mode::Solve(vec![(
+            parse_number(day, "day")?,
+            vec![parse_number(part, "part")?],
+        )]),
+        _ => return Err(USAGE.to_string()),
+    };
+
+    match (&mode, format) {
+        (_, Format::Text) | (Mode::Solve(_), Format::Json) | (Mode::Frames { .. }, Format::Pbm) => {
+        }
+        (Mode::Solve(_), _) => return Err(String::from("Answers can be text or json")),
+        (Mode::Frames { .. }, _) => return Err(String::from("Frames can be text or pbm")),
+        _ => {
+            return Err(String::from(
+                "--format can only be used when solving days or with frames",
+            ))
+        }
+    }
+
+    Ok(Args {
+        mode,
+        format,
+        source,
+    })
+}
+
+/// Solves each of `tasks`, returns whether all of them succeeded
+fn solve(tasks: Vec<(u8, Vec<u8>)>, format: Format, source: &Source) -> bool {
+    let mut failed = false;
+    for (day, parts) in tasks {
+        let answers = source
+            .read(day)
+            .map_err(|e| e.into())
+            .and_then(|input| run(day, &parts, &input, &source.name(day)));
+
+        let answers = match answers {
+            Ok(a) => a,
+            Err(e) => {
+                eprintln!("Day {} failed: {}", day, e);
+                failed = true;
+                continue;
+            }
+        };
+
+        for answer in answers {
+            failed |= answer.value.is_err();
+            match (format, &answer.value) {
+                (Format::Json, _) => println!("{}", answer.to_json()),
+                (_, Err(e)) => eprintln!("Day {} part {} failed: {}", day, answer.part, e),
+                // multi-line answers (like pictures) start on their own line
+                (_, Ok(value)) if value.contains('\n') => {
+                    println!("Day {} part {}:\n{}", day, answer.part, value)
+                }
+                (_, Ok(value)) => println!("Day {} part {}: {}", day, answer.part, value),
+            }
+        }
+    }
+    !failed
+}
+
+/// Times every day of `days`, returns whether all of them succeeded
+fn run_bench(days: &[u8], reps: usize, output: &str, source: &Source) -> bool {
+    let mut failed = false;
+    let mut timings = Vec::new();
+
+    println!(
+        "{:>3} {:<6} {:>12} {:>12} {:>12}",
+        "day", "stage", "min", "median", "max"
+    );
+    for &day in days {
+        let result = source
+            .read(day)
+            .map_err(|e| e.into())
+            .and_then(|input| bench(day, &input, &source.name(day), reps));
+
+        match result {
+            Ok(t) => {
+                for t in &t {
+                    println!(
+                        "{:>3} {:<6} {:>12} {:>12} {:>12}",
+                        t.day,
+                        t.stage,
+                        format!("{:.3?}", t.min()),
+                        format!("{:.3?}", t.median()),
+                        format!("{:.3?}", t.max())
+                    );
+                }
+                timings.extend(t);
+            }
+            Err(e) => {
+                eprintln!("Day {} failed: {}", day, e);
+                failed = true;
+            }
+        }
+    }
+
+    if let Err(e) = fs::write(output, to_csv(&timings)) {
+        eprintln!("Could not write '{}': {}", output, e);
+        return false;
+    }
+    println!("Wrote the summary to {}", output);
+    !failed
+}
+
+/// Solves every day and compares the answers, returns whether none failed
+///
+/// Days without any expected answer are reported as missing and not run.
+fn verify(path: &str, source: &Source) -> bool {
+    let answers = match fs::read_to_string(path) {
+        Ok(input) => Answers::parse(&input).map_err(|e| e.in_file(path).to_string()),
+        Err(e) => Err(format!("Could not read '{}': {}", path, e)),
+    };
+    let answers = match answers {
+        Ok(a) => a,
+        Err(e) => {
+            eprintln!("{}", e);
+            return false;
+        }
+    };
+
+    let (mut passed, mut failed, mut missing) = (0, 0, 0);
+    for day in 1..=DAYS {
+        if !answers.has_day(day) {
+            println!("Day {}: missing", day);
+            missing += 2;
+            continue;
+        }
+
+        let results = source
+            .read(day)
+            .map_err(|e| e.into())
+            .and_then(|input| run(day, &[1, 2], &input, &source.name(day)));
+        let results = match results {
+            Ok(r) => r,
+            Err(e) => {
+                println!("Day {}: FAIL ({})", day, e);
+                failed += 2;
+                continue;
+            }
+        };
+
+        for answer in results {
+            let (part, answer) = match (answer.part, answer.value) {
+                (part, Ok(answer)) => (part, answer),
+                (part, Err(e)) => {
+                    println!("Day {} part {}: FAIL ({})", day, part, e);
+                    failed += 1;
+                    continue;
+                }
+            };
+            let verdict = answers.check(day, part, &answer);
+            match verdict {
+                Verdict::Pass => passed += 1,
+                Verdict::Fail { .. } => failed += 1,
+                Verdict::Missing => missing += 1,
+            }
+            match verdict {
+                Verdict::Fail { .. } if !answer.contains('\n') => {
+                    println!("Day {} part {}: {}, got {}", day, part, verdict, answer)
+                }
+                _ => println!("Day {} part {}: {}", day, part, verdict),
+            }
+        }
+    }
+
+    println!("{} passed, {} failed, {} missing", passed, failed, missing);
+    failed == 0
+}
+
+/// Writes every stage of the day 13 folds into the directory `output`
+fn frames(output: &str, format: Format, source: &Source) -> Result<(), Box<dyn Error>> {
+    let (paper, folds) = read_input::<Day13>(source)?;
+    let stages = paper.stages(&folds)?;
+
+    fs::create_dir_all(output)?;
+    for (i, stage) in stages.iter().enumerate() {
+        let (content, extension) = match format {
+            Format::Pbm => (stage.to_pbm()?, "pbm"),
+            _ => (stage.to_text()?.into_bytes(), "txt"),
+        };
+        let path = format!("{}/fold_{:02}.{}", output, i, extension);
+        fs::write(&path, content).map_err(|e| format!("Could not write '{}': {}", path, e))?;
+
+        match i {
+            0 => println!("{}: {}x{}", path, stage.width(), stage.height()),
+            _ => println!(
+                "{}: {}x{} after {}",
+                path,
+                stage.width(),
+                stage.height(),
+                folds[i - 1]
+            ),
+        }
+    }
+    Ok(())
+}
+
+/// Prints the day 6 population of every day up to `days`, or writes it to `output`
+fn series(
+    days: u32,
+    buckets: bool,
+    output: Option<&str>,
+    source: &Source,
+) -> Result<(), Box<dyn Error>> {
+    let fish = read_input::<Day6>(source)?;
+    let csv = day6::series_csv(&Population::default().series(&fish, days)?, buckets);
+
+    match output {
+        Some(path) => {
+            fs::write(path, csv).map_err(|e| format!("Could not write '{}': {}", path, e))?
+        }
+        None => print!("{}", csv),
+    }
+    Ok(())
+}
+
+/// Prints statistics and a sparkline of the day 1 depths
+fn profile(width: usize, source: &Source) -> Result<(), Box<dyn Error>> {
+    let depths = read_input::<Day1>(source)?;
+    let profile = Profile::new(&depths).ok_or("There are no depths")?;
+
+    print!("{}", profile);
+    println!("{}", day1::sparkline(&depths, width));
+    Ok(())
+}
+
+/// Prints the error of a `result` for `day`, returns whether there was none
+fn reported(day: u8, result: Result<(), Box<dyn Error>>) -> bool {
+    match result {
+        Ok(()) => true,
+        Err(e) => {
+            eprintln!("Day {} failed: {}", day, e);
+            false
+        }
+    }
+}
+
+/// Reads and parses the input of a single day
+fn read_input<S: Solution>(source: &Source) -> Result<S::Input, Box<dyn Error>> {
+    let input = source.read(S::DAY)?;
+    Ok(S::parse(&input).map_err(|e| e.in_file(&source.name(S::DAY)))?)
+}
+
+/// Runs every day 15 search algorithm, returns whether they agree on the risk
+fn search(tiles: usize, path: bool, source: &Source) -> bool {
+    let field = match read_input::<Day15>(source) {
+        Ok(f) => f,
+        Err(e) => {
+            eprintln!("Day 15 failed: {}", e);
+            return false;
+        }
+    };
+
+    let cave = Tiled::new(&field, tiles);
+    let results = day15::compare(&cave);
+    println!(
+        "{:<15} {:>8} {:>10} {:>12}",
+        "algorithm", "risk", "expanded", "time"
+    );
+    for r in &results {
+        println!(
+            "{:<15} {:>8} {:>10} {:>12}",
+            r.algorithm,
+            r.risk,
+            r.expanded,
+            format!("{:.3?}", r.time)
+        );
+    }
+
+    if path {
+        println!();
+        print!("{}", day15::overlay(&cave, &day15::dijkstra(&cave)));
+    }
+
+    let agree = results.iter().all(|r| r.risk == results[0].risk);
+    if !agree {
+        eprintln!("The algorithms disagree on the lowest risk");
+    }
+    agree
+}
+
+fn main() {
+    let args: Vec<String> = env::args().skip(1).collect();
+    let args = match parse_args(&args) {
+        Ok(a) => a,
+        Err(e) => {
+            eprintln!("{}", e);
+            process::exit(2);
+        }
+    };
+
+    let ok = match args.mode {
+        Mode::Solve(tasks) => solve(tasks, args.format, &args.source),
+        Mode::Search { tiles, path } => search(tiles, path, &args.source),
+        Mode::Bench { days, reps, output } => run_bench(&days, reps, &output, &args.source),
+        Mode::Verify { answers } => verify(&answers, &args.source),
+        Mode::Frames { output } => reported(13, frames(&output, args.format, &args.source)),
+        Mode::Series {
+            days,
+            buckets,
+            output,
+        } => reported(6, series(days, buckets, output.as_deref(), &args.source)),
+        Mode::Profile { width } => reported(1, profile(width, &args.source)),
+    };
+
+    if !ok {
+        process::exit(1);
+    }
+}
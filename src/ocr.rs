@@ -0,0 +1,186 @@
+//! Reading the capital letters that dots on a grid spell out
+//!
+//! The letters are 4 cells wide and 6 cells high, with one empty column
+//! between neighbouring letters.
+
+use std::error::Error;
+use std::fmt::{self, Display};
+
+use crate::grid::Grid;
+
+const GLYPH_WIDTH: usize = 4;
+const GLYPH_HEIGHT: usize = 6;
+/// Most letters read at once, far more than any puzzle spells out
+pub const MAX_LETTERS: usize = 64;
+
+/// Every known letter, with '#' for a dot
+const GLYPHS: [(char, [&str; GLYPH_HEIGHT]); 17] = [
+    ('A', [".##.", "#..#", "#..#", "####", "#..#", "#..#"]),
+    ('B', ["###.", "#..#", "###.", "#..#", "#..#", "###."]),
+    ('C', [".##.", "#..#", "#...", "#...", "#..#", ".##."]),
+    ('E', ["####", "#...", "###.", "#...", "#...", "####"]),
+    ('F', ["####", "#...", "###.", "#...", "#...", "#..."]),
+    ('G', [".##.", "#..#", "#...", "#.##", "#..#", ".###"]),
+    ('H', ["#..#", "#..#", "####", "#..#", "#..#", "#..#"]),
+    ('I', [".###", "..#.", "..#.", "..#.", "..#.", ".###"]),
+    ('J', ["..##", "...#", "...#", "...#", "#..#", ".##."]),
+    ('K', ["#..#", "#.#.", "##..", "#.#.", "#.#.", "#..#"]),
+    ('L', ["#...", "#...", "#...", "#...", "#...", "####"]),
+    ('O', [".##.", "#..#", "#..#", "#..#", "#..#", ".##."]),
+    ('P', ["###.", "#..#", "#..#", "###.", "#...", "#..."]),
+    ('R', ["###.", "#..#", "#..#", "###.", "#.#.", "#..#"]),
+    ('S', [".###", "#...", "#...", ".##.", "...#", "###."]),
+    ('U', ["#..#", "#..#", "#..#", "#..#", "#..#", ".##."]),
+    ('Z', ["####", "...#", "..#.", ".#..", "#...", "####"]),
+];
+
+/// Why the dots could not be read as letters
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum OcrError {
+    /// The grid is not as high as a letter
+    Height(usize),
+    /// The grid is wider than [`MAX_LETTERS`] letters
+    Width(usize),
+    /// The letter at `index` (starting at 0) is not known
+    UnknownGlyph { index: usize, glyph: String },
+    /// There is a dot between or after the letters
+    Stray { x: usize, y: usize },
+}
+
+impl Display for OcrError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            OcrError::Height(height) => write!(
+                f,
+                "letters are {} dots high, the dots are {} high",
+                GLYPH_HEIGHT, height
+            ),
+            OcrError::Width(width) => write!(
+                f,
+                "at most {} letters are read, the dots are {} wide",
+                MAX_LETTERS, width
+            ),
+            OcrError::UnknownGlyph { index, glyph } => {
+                write!(f, "unknown letter {}:\n{}", index + 1, glyph)
+            }
+            OcrError::Stray { x, y } => write!(f, "the dot at {},{} is no part of a letter", x, y),
+        }
+    }
+}
+
+impl Error for OcrError {}
+
+/// Whether dots of `width` x `height` can be letters at all
+///
+/// Check this before building a grid of dots that might be huge.
+pub fn check_size(width: usize, height: usize) -> Result<(), OcrError> {
+    if height != GLYPH_HEIGHT {
+        return Err(OcrError::Height(height));
+    }
+    if width > MAX_LETTERS * (GLYPH_WIDTH + 1) {
+        return Err(OcrError::Width(width));
+    }
+    Ok(())
+}
+
+/// The letters spelled out by the dots (`true`) in `dots`
+pub fn read(dots: &Grid<bool>) -> Result<String, OcrError> {
+    check_size(dots.width(), dots.height())?;
+
+    // the empty column after the last letter may have been cut off
+    let letters = (dots.width() + 1) / (GLYPH_WIDTH + 1);
+    let in_letter =
+        |x: usize| x % (GLYPH_WIDTH + 1) < GLYPH_WIDTH && x < letters * (GLYPH_WIDTH + 1);
+    for y in 0..GLYPH_HEIGHT {
+        if let Some(x) = (0..dots.width()).find(|&x| dots[(x, y)] && !in_letter(x)) {
+            return Err(OcrError::Stray { x, y });
+        }
+    }
+
+    (0..letters)
+        .map(|index| {
+            let left = index * (GLYPH_WIDTH + 1);
+            let rows: Vec<String> = (0..GLYPH_HEIGHT)
+                .map(|y| {
+                    (left..left + GLYPH_WIDTH)
+                        .map(|x| match dots[(x, y)] {
+                            true => '#',
+                            false => '.',
+                        })
+                        .collect()
+                })
+                .collect();
+
+            GLYPHS
+                .iter()
+                .find(|(_, glyph)| glyph.iter().eq(rows.iter()))
+                .map(|&(letter, _)| letter)
+                .ok_or_else(|| OcrError::UnknownGlyph {
+                    index,
+                    glyph: rows.join("\n"),
+                })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Draws `rows` of '#' and '.' as a grid
+    fn dots(rows: &[&str]) -> Grid<bool> {
+        let cells = rows.iter().flat_map(|r| r.chars().map(|c| c == '#'));
+        Grid::from_vec(rows[0].len(), rows.len(), cells.collect())
+    }
+
+    #[test]
+    fn test_read() {
+        let hi = dots(&[
+            "#..#..###",
+            "#..#...#.",
+            "####...#.",
+            "#..#...#.",
+            "#..#...#.",
+            "#..#..###",
+        ]);
+        assert_eq!(read(&hi), Ok(String::from("HI")));
+
+        for (letter, glyph) in GLYPHS {
+            assert_eq!(read(&dots(&glyph)), Ok(letter.to_string()));
+        }
+    }
+
+    #[test]
+    fn test_errors() {
+        let unknown = dots(&["#.#.", "#.#.", "#.#.", "#.#.", "#.#.", "#.#."]);
+        assert_eq!(
+            read(&unknown),
+            Err(OcrError::UnknownGlyph {
+                index: 0,
+                glyph: String::from("#.#.\n#.#.\n#.#.\n#.#.\n#.#.\n#.#."),
+            })
+        );
+
+        assert_eq!(read(&dots(&["#..#"; 5])), Err(OcrError::Height(5)));
+        assert_eq!(check_size(1000, 6), Err(OcrError::Width(1000)));
+        assert_eq!(check_size(320, 6), Ok(()));
+
+        // a dot in the gap, and one in columns too few for another letter
+        let gap = dots(&[
+            "#..#..###",
+            "#..#...#.",
+            "#####..#.",
+            "#..#...#.",
+            "#..#...#.",
+            "#..#..###",
+        ]);
+        assert_eq!(read(&gap), Err(OcrError::Stray { x: 4, y: 2 }));
+
+        let l = ["#.....", "#.....", "#.....", "#.....", "#.....", "####.#"];
+        assert_eq!(read(&dots(&l)), Err(OcrError::Stray { x: 5, y: 5 }));
+        let l = [
+            "#......", "#......", "#......", "#......", "#......", "####...",
+        ];
+        assert_eq!(read(&dots(&l)), Ok(String::from("L")));
+    }
+}
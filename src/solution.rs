@@ -0,0 +1,54 @@
+use std::error::Error;
+use std::fmt::Display;
+use std::fs;
+
+use crate::bigint::BigUint;
+use crate::error::ParseError;
+
+/// What a part returns: its answer, or why there is none
+pub trait Outcome {
+    fn into_answer(self) -> Result<String, String>;
+}
+
+/// Types that always are an answer
+macro_rules! answer_types {
+    ($($t:ty),*) => {
+        $(impl Outcome for $t {
+            fn into_answer(self) -> Result<String, String> {
+                Ok(self.to_string())
+            }
+        })*
+    };
+}
+
+answer_types!(i32, u32, u64, usize, String, BigUint);
+
+impl<T: Display, E: Display> Outcome for Result<T, E> {
+    fn into_answer(self) -> Result<String, String> {
+        self.map(|a| a.to_string()).map_err(|e| e.to_string())
+    }
+}
+
+/// The common interface of every day
+///
+/// The input is parsed once and then shared by both parts.
+pub trait Solution {
+    /// Number of the day in the advent calendar
+    const DAY: u8;
+
+    /// Parsed puzzle input
+    type Input;
+    type AnswerOne: Outcome;
+    type AnswerTwo: Outcome;
+
+    /// Parses the content of a puzzle input
+    fn parse(input: &str) -> Result<Self::Input, ParseError>;
+    fn part_one(input: &Self::Input) -> Self::AnswerOne;
+    fn part_two(input: &Self::Input) -> Self::AnswerTwo;
+
+    /// Reads `filename` and parses its content
+    fn parse_file(filename: &str) -> Result<Self::Input, Box<dyn Error + 'static>> {
+        let input = fs::read_to_string(filename)?;
+        Ok(Self::parse(&input).map_err(|e| e.in_file(filename))?)
+    }
+}